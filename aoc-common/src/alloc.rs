@@ -0,0 +1,110 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+
+/// The system allocator, counting what goes through it for [`measured`].
+///
+/// It only counts once it is the global allocator of the program:
+///
+/// ```ignore
+/// #[global_allocator]
+/// static ALLOC: aoc_common::CountingAlloc = aoc_common::CountingAlloc;
+/// ```
+pub struct CountingAlloc;
+
+fn allocated(size: usize) {
+    ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+    ALLOCATED.fetch_add(size, Ordering::Relaxed);
+    let current = CURRENT.fetch_add(size, Ordering::Relaxed) + size;
+    PEAK.fetch_max(current, Ordering::Relaxed);
+}
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            allocated(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            allocated(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        CURRENT.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            // counts as a new allocation of the whole size, the old one is freed
+            CURRENT.fetch_sub(layout.size(), Ordering::Relaxed);
+            allocated(new_size);
+        }
+        new_ptr
+    }
+}
+
+/// What was allocated while running something, see [`measured`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AllocStats {
+    /// The most bytes in use at once, above what was in use before.
+    pub peak: usize,
+    /// How many allocations were made, a reallocation counts as one.
+    pub allocations: usize,
+    /// The bytes of all the allocations together, freed or not.
+    pub allocated: usize,
+}
+
+/// Runs `f` and returns its result with what it allocated.
+///
+/// The counters are shared by all threads, what other threads allocate in the meantime counts
+/// too. Everything is 0 unless [`CountingAlloc`] is the global allocator.
+pub fn measured<T>(f: impl FnOnce() -> T) -> (T, AllocStats) {
+    let start = CURRENT.load(Ordering::Relaxed);
+    PEAK.store(start, Ordering::Relaxed);
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+    let allocated = ALLOCATED.load(Ordering::Relaxed);
+    let result = f();
+    let stats = AllocStats {
+        peak: PEAK.load(Ordering::Relaxed).saturating_sub(start),
+        allocations: ALLOCATIONS.load(Ordering::Relaxed) - allocations,
+        allocated: ALLOCATED.load(Ordering::Relaxed) - allocated,
+    };
+    (result, stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[global_allocator]
+    static ALLOC: CountingAlloc = CountingAlloc;
+
+    #[test]
+    fn counts_allocations() {
+        let (len, stats) = measured(|| {
+            let mut v: Vec<u8> = Vec::with_capacity(4096);
+            v.extend(std::iter::repeat_n(1, 4096));
+            let copy = v.clone();
+            drop(v);
+            copy.len()
+        });
+        assert_eq!(len, 4096);
+        // the tests run on several threads, which may allocate at the same time
+        assert!(stats.allocations >= 2, "{stats:?}");
+        assert!(stats.allocated >= 2 * 4096, "{stats:?}");
+        assert!(stats.peak > 0, "{stats:?}");
+    }
+}
@@ -0,0 +1,205 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+use crate::{Error, Result};
+
+/// A small random number generator (SplitMix64), so that a seed gives the same input on every
+/// machine and with every version of the dependencies.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number from 0 to `n` excluded.
+    ///
+    /// # Panics
+    ///
+    /// If `n` is 0.
+    pub fn below(&mut self, n: u64) -> u64 {
+        assert!(n > 0, "no number is below 0");
+        // drop the numbers of the last incomplete cycle, they would favor the small results
+        let limit = u64::MAX - u64::MAX % n;
+        loop {
+            let x = self.next_u64();
+            if x < limit {
+                return x % n;
+            }
+        }
+    }
+
+    /// A number from `low` to `high`, both included.
+    pub fn between(&mut self, low: i64, high: i64) -> i64 {
+        assert!(low <= high, "{low}..={high} is empty");
+        let span = high.abs_diff(low);
+        if span == u64::MAX {
+            return self.next_u64() as i64;
+        }
+        low.wrapping_add(self.below(span + 1) as i64)
+    }
+
+    /// An index into a slice of `len` items.
+    pub fn index(&mut self, len: usize) -> usize {
+        self.below(len as u64) as usize
+    }
+
+    /// `true` with the probability `p`.
+    pub fn chance(&mut self, p: f64) -> bool {
+        ((self.next_u64() >> 11) as f64 / (1u64 << 53) as f64) < p
+    }
+
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.index(items.len())]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.index(i + 1));
+        }
+    }
+}
+
+/// A setting of a [`Generator`] besides the size, like the number of monkeys.
+pub struct Knob {
+    pub name: &'static str,
+    pub about: &'static str,
+    pub default: &'static str,
+}
+
+/// The values of the knobs of a generator, the defaults unless given on the command line.
+pub struct Knobs {
+    values: Vec<(&'static str, String)>,
+}
+
+impl Knobs {
+    /// Fails when a given knob is not one of `knobs`.
+    pub fn new(knobs: &'static [Knob], given: &[(String, String)]) -> Result<Self> {
+        let mut values: Vec<_> = knobs
+            .iter()
+            .map(|k| (k.name, k.default.to_string()))
+            .collect();
+        for (name, value) in given {
+            match values.iter_mut().find(|(n, _)| n == name) {
+                Some((_, v)) => *v = value.clone(),
+                None if knobs.is_empty() => {
+                    return Err(Error::parse(format!(
+                        "there is no knob {name:?}, there are none"
+                    )))
+                }
+                None => {
+                    let names: Vec<_> = knobs.iter().map(|k| k.name).collect();
+                    return Err(Error::parse(format!(
+                        "there is no knob {name:?}, there are: {}",
+                        names.join(", ")
+                    )));
+                }
+            }
+        }
+        Ok(Knobs { values })
+    }
+
+    /// The value of the knob `name`, which must be one of the knobs of the generator.
+    pub fn get<T>(&self, name: &str) -> Result<T>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        let Some((_, value)) = self.values.iter().find(|(n, _)| *n == name) else {
+            return Err(Error::Invariant(format!(
+                "the generator has no knob {name:?}"
+            )));
+        };
+        value
+            .parse()
+            .map_err(|e| Error::parse(format!("knob {name} = {value:?}: {e}")))
+    }
+}
+
+/// Makes up puzzle inputs of any size, to see how a day scales.
+pub struct Generator {
+    /// What the size counts, like "elves".
+    pub size: &'static str,
+    /// About the size of the real input.
+    pub default_size: usize,
+    pub knobs: &'static [Knob],
+    /// Makes an input that only depends on the size, the knobs and the random numbers.
+    ///
+    /// A size too small for a valid input gets the smallest valid one instead.
+    pub generate: fn(&mut Rng, usize, &Knobs) -> Result<String>,
+}
+
+impl Generator {
+    pub fn generate(
+        &self,
+        size: Option<usize>,
+        seed: u64,
+        knobs: &[(String, String)],
+    ) -> Result<String> {
+        let knobs = Knobs::new(self.knobs, knobs)?;
+        (self.generate)(
+            &mut Rng::new(seed),
+            size.unwrap_or(self.default_size),
+            &knobs,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rng() {
+        let mut rng = Rng::new(42);
+        let first: Vec<_> = (0..4).map(|_| rng.next_u64()).collect();
+        let mut again = Rng::new(42);
+        assert!(first.iter().all(|&n| n == again.next_u64()));
+
+        for _ in 0..1000 {
+            assert!(rng.below(7) < 7);
+            assert!((-3..=3).contains(&rng.between(-3, 3)));
+        }
+        assert_eq!(rng.between(5, 5), 5);
+        assert!((0..1000).all(|_| !rng.chance(0.0) && rng.chance(1.0)));
+
+        let mut items: Vec<_> = (0..10).collect();
+        rng.shuffle(&mut items);
+        items.sort();
+        assert_eq!(items, (0..10).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn knobs() {
+        const KNOBS: &[Knob] = &[Knob {
+            name: "monkeys",
+            about: "number of monkeys",
+            default: "8",
+        }];
+        let knobs = Knobs::new(KNOBS, &[]).unwrap();
+        assert_eq!(knobs.get::<usize>("monkeys").unwrap(), 8);
+        let given = [("monkeys".to_string(), "3".to_string())];
+        let knobs = Knobs::new(KNOBS, &given).unwrap();
+        assert_eq!(knobs.get::<usize>("monkeys").unwrap(), 3);
+
+        let given = [("apes".to_string(), "3".to_string())];
+        assert!(Knobs::new(KNOBS, &given).is_err());
+        let given = [("monkeys".to_string(), "many".to_string())];
+        let knobs = Knobs::new(KNOBS, &given).unwrap();
+        assert!(matches!(
+            knobs.get::<usize>("monkeys"),
+            Err(Error::Parse { .. })
+        ));
+    }
+}
@@ -0,0 +1,216 @@
+//! Code shared by every day: the error type, input loading, timing, allocation counting and input
+//! generation.
+
+use std::fmt;
+use std::fs;
+use std::io::{self, Read};
+use std::num::ParseIntError;
+use std::path::Path;
+use std::time::Duration;
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Instant;
+
+#[cfg(target_arch = "wasm32")]
+use web_time::Instant;
+
+mod alloc;
+mod generate;
+mod solution;
+
+pub use alloc::{measured, AllocStats, CountingAlloc};
+pub use generate::{Generator, Knob, Knobs, Rng};
+pub use solution::{Animation, Answer, Part, Screen, Solution, Variant};
+
+pub type Result<T> = ::std::result::Result<T, Error>;
+
+/// Returns early with an [`Error::Parse`] built from a format string.
+#[macro_export]
+macro_rules! parse_err {
+    ($($tt:tt)*) => { Err($crate::Error::parse(format!($($tt)*))) }
+}
+
+/// Returns early with an [`Error::Unsolvable`] built from a format string.
+#[macro_export]
+macro_rules! unsolvable {
+    ($($tt:tt)*) => { Err($crate::Error::Unsolvable(format!($($tt)*))) }
+}
+
+/// Returns early with an [`Error::Invariant`] built from a format string.
+#[macro_export]
+macro_rules! invariant {
+    ($($tt:tt)*) => { Err($crate::Error::Invariant(format!($($tt)*))) }
+}
+
+#[derive(Debug)]
+pub enum Error {
+    /// The input does not follow the puzzle format.
+    ///
+    /// `line` and `column` are 1-based, 0 means the position is unknown.
+    Parse {
+        line: usize,
+        column: usize,
+        message: String,
+    },
+    /// The input is well formed but has no answer.
+    Unsolvable(String),
+    /// Something the solver relies on turned out to be false.
+    Invariant(String),
+    /// The input could not be read.
+    Io(io::Error),
+}
+
+impl Error {
+    pub fn parse(message: impl Into<String>) -> Self {
+        Error::Parse {
+            line: 0,
+            column: 0,
+            message: message.into(),
+        }
+    }
+
+    /// Sets the position of a parse error, unless it already knows one.
+    pub fn at(self, line: usize, column: usize) -> Self {
+        match self {
+            Error::Parse {
+                line: 0,
+                column: 0,
+                message,
+            } => Error::Parse {
+                line,
+                column,
+                message,
+            },
+            e => e,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Parse {
+                line: 0, message, ..
+            } => write!(f, "parse error: {message}"),
+            Error::Parse {
+                line,
+                column: 0,
+                message,
+            } => write!(f, "parse error at line {line}: {message}"),
+            Error::Parse {
+                line,
+                column,
+                message,
+            } => write!(f, "parse error at line {line}, column {column}: {message}"),
+            Error::Unsolvable(message) => write!(f, "unsolvable input: {message}"),
+            Error::Invariant(message) => write!(f, "invariant violated: {message}"),
+            Error::Io(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+impl From<ParseIntError> for Error {
+    fn from(e: ParseIntError) -> Self {
+        Error::parse(e.to_string())
+    }
+}
+
+/// Attaches an input position to the parse error of a result.
+pub trait Position<T> {
+    /// `line` is 1-based, like the line numbers of a text editor.
+    fn at_line(self, line: usize) -> Result<T>;
+
+    fn at(self, line: usize, column: usize) -> Result<T>;
+}
+
+impl<T, E: Into<Error>> Position<T> for ::std::result::Result<T, E> {
+    fn at_line(self, line: usize) -> Result<T> {
+        self.at(line, 0)
+    }
+
+    fn at(self, line: usize, column: usize) -> Result<T> {
+        self.map_err(|e| e.into().at(line, column))
+    }
+}
+
+/// Reads the whole puzzle input from stdin.
+pub fn read_stdin() -> Result<String> {
+    let mut input = String::new();
+    io::stdin().read_to_string(&mut input)?;
+    Ok(input)
+}
+
+/// Reads the whole puzzle input from a file, naming the file when it fails.
+pub fn read_input(path: impl AsRef<Path>) -> Result<String> {
+    let path = path.as_ref();
+    fs::read_to_string(path)
+        .map_err(|e| Error::Io(io::Error::new(e.kind(), format!("{}: {e}", path.display()))))
+}
+
+/// Hashes the puzzle input with 64-bit FNV-1a, so that recorded answers can be tied to the input
+/// they were computed from without storing the input itself.
+pub fn input_hash(input: &str) -> String {
+    let hash = input.bytes().fold(0xcbf29ce484222325u64, |hash, b| {
+        (hash ^ b as u64).wrapping_mul(0x100000001b3)
+    });
+    format!("{hash:016x}")
+}
+
+/// Runs `f` and returns its result with the time it took.
+pub fn timed<T>(f: impl FnOnce() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let result = f();
+    (result, start.elapsed())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_error_position() {
+        let e: Result<i32> = "x".parse::<i32>().at_line(3);
+        assert!(matches!(
+            e,
+            Err(Error::Parse {
+                line: 3,
+                column: 0,
+                ..
+            })
+        ));
+
+        let e = Error::parse("bad").at(2, 5).at(7, 1);
+        assert_eq!(e.to_string(), "parse error at line 2, column 5: bad");
+        assert_eq!(Error::parse("bad").to_string(), "parse error: bad");
+    }
+
+    #[test]
+    fn fnv1a_hash() {
+        assert_eq!(input_hash(""), "cbf29ce484222325");
+        assert_eq!(input_hash("a"), "af63dc4c8601ec8c");
+    }
+
+    #[test]
+    fn macros() {
+        let e: Result<()> = unsolvable!("no path from {} to {}", 1, 2);
+        assert!(matches!(e, Err(Error::Unsolvable(m)) if m == "no path from 1 to 2"));
+        let e: Result<()> = invariant!("empty stack");
+        assert!(matches!(e, Err(Error::Invariant(_))));
+        let e: Result<()> = parse_err!("bad {}", "line");
+        assert!(matches!(e, Err(Error::Parse { line: 0, .. })));
+    }
+}
@@ -0,0 +1,135 @@
+use std::fmt;
+
+use crate::{Generator, Result};
+
+/// A puzzle answer, ready to be printed or compared with a recorded one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Number(i64),
+    Text(String),
+    /// The part has nothing to compute, like the second part of the last day.
+    None,
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Answer::Number(n) => write!(f, "{n}"),
+            Answer::Text(s) => write!(f, "{s}"),
+            Answer::None => write!(f, "-"),
+        }
+    }
+}
+
+macro_rules! from_signed {
+    ($($t:ty),*) => {
+        $(impl From<$t> for Answer {
+            fn from(n: $t) -> Self {
+                Answer::Number(n as i64)
+            }
+        })*
+    };
+}
+
+macro_rules! from_unsigned {
+    ($($t:ty),*) => {
+        $(impl From<$t> for Answer {
+            fn from(n: $t) -> Self {
+                match i64::try_from(n) {
+                    Ok(n) => Answer::Number(n),
+                    Err(_) => Answer::Text(n.to_string()),
+                }
+            }
+        })*
+    };
+}
+
+from_signed!(i8, i16, i32, i64);
+from_unsigned!(u8, u16, u32, u64, usize);
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Answer::Text(s)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(s: &str) -> Self {
+        Answer::Text(s.to_string())
+    }
+}
+
+/// A function solving one part of a puzzle from its parsed input.
+pub type Part<I> = fn(&I) -> Result<Answer>;
+
+/// An alternative implementation of one part, kept to compare it with the default one.
+///
+/// Variants often parse the input their own way, so they start from the raw input.
+pub struct Variant {
+    pub name: &'static str,
+    pub part: u8,
+    pub solve: fn(&str) -> Result<Answer>,
+}
+
+/// Where an [`Animation`] shows its frames, like a terminal.
+pub trait Screen {
+    /// Called before the first frame of every animation.
+    fn begin(&mut self, _animation: &Animation) {}
+
+    /// Shows one step of the simulation, drawn as text.
+    ///
+    /// Returns `false` when the viewer does not want to see more, the simulation should stop.
+    fn show(&mut self, frame: &str) -> bool;
+}
+
+/// Any closure can be a screen, to collect the frames in tests for example.
+impl<F: FnMut(&str) -> bool> Screen for F {
+    fn show(&mut self, frame: &str) -> bool {
+        self(frame)
+    }
+}
+
+/// A simulation of one part that can be watched step by step.
+///
+/// Like a variant it starts from the raw input.
+pub struct Animation {
+    pub name: &'static str,
+    pub part: u8,
+    pub animate: fn(&str, &mut dyn Screen) -> Result<()>,
+}
+
+/// One day of the puzzle: parse the input once, then solve both parts from it.
+pub trait Solution {
+    type Input;
+
+    fn parse(input: &str) -> Result<Self::Input>;
+
+    fn part1(input: &Self::Input) -> Result<Answer>;
+
+    fn part2(input: &Self::Input) -> Result<Answer>;
+
+    fn variants() -> Vec<Variant> {
+        Vec::new()
+    }
+
+    fn animations() -> Vec<Animation> {
+        Vec::new()
+    }
+
+    fn generator() -> Option<Generator> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn answer_display() {
+        assert_eq!(Answer::from(-3i32).to_string(), "-3");
+        assert_eq!(Answer::from(u64::MAX).to_string(), u64::MAX.to_string());
+        assert_eq!(Answer::from("RGB").to_string(), "RGB");
+        assert_eq!(Answer::None.to_string(), "-");
+    }
+}
@@ -0,0 +1,299 @@
+//! A rectangular grid stored row by row, shared by the days whose input is a map.
+//!
+//! Positions are `(row, column)` pairs, the first row is the top line of the input.
+
+use std::ops::{Index, IndexMut};
+
+use aoc_common::{parse_err, Position, Result};
+
+pub type Pos = (usize, usize);
+
+/// A step on the grid, as `(rows, columns)`.
+pub type Offset = (isize, isize);
+
+pub const UP: Offset = (-1, 0);
+pub const RIGHT: Offset = (0, 1);
+pub const DOWN: Offset = (1, 0);
+pub const LEFT: Offset = (0, -1);
+
+/// The four neighbors sharing a side, clockwise from the top.
+pub const DIRECTIONS4: [Offset; 4] = [UP, RIGHT, DOWN, LEFT];
+
+/// The eight neighbors sharing a side or a corner, clockwise from the top.
+pub const DIRECTIONS8: [Offset; 8] = [UP, (-1, 1), RIGHT, (1, 1), DOWN, (1, -1), LEFT, (-1, -1)];
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// Fails on the first row that is not as long as the first one.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Result<Self> {
+        let width = rows.first().map_or(0, |r| r.len());
+        let height = rows.len();
+        let mut cells = Vec::with_capacity(width * height);
+        for (i, row) in rows.into_iter().enumerate() {
+            if row.len() != width {
+                return parse_err!("the grid is not a rectangle").at_line(i + 1);
+            }
+            cells.extend(row);
+        }
+        Ok(Grid {
+            width,
+            height,
+            cells,
+        })
+    }
+
+    /// Parses one cell per character, the errors of `f` get the position of the character.
+    pub fn parse(input: &str, f: impl FnMut(char) -> Result<T>) -> Result<Self> {
+        Grid::from_rows(parse_rows(input, f)?)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, (row, column): Pos) -> bool {
+        row < self.height && column < self.width
+    }
+
+    pub fn get(&self, pos: Pos) -> Option<&T> {
+        if self.contains(pos) {
+            Some(&self.cells[pos.0 * self.width + pos.1])
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, pos: Pos) -> Option<&mut T> {
+        if self.contains(pos) {
+            Some(&mut self.cells[pos.0 * self.width + pos.1])
+        } else {
+            None
+        }
+    }
+
+    /// Like [`Grid::get`], for coordinates that may be negative.
+    pub fn get_signed(&self, (row, column): Offset) -> Option<&T> {
+        let row = usize::try_from(row).ok()?;
+        let column = usize::try_from(column).ok()?;
+        self.get((row, column))
+    }
+
+    /// The position one `offset` away from `pos`, if it is still on the grid.
+    pub fn offset(&self, (row, column): Pos, (d_row, d_column): Offset) -> Option<Pos> {
+        let pos = (
+            row.checked_add_signed(d_row)?,
+            column.checked_add_signed(d_column)?,
+        );
+        self.contains(pos).then_some(pos)
+    }
+
+    pub fn neighbors4(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        DIRECTIONS4
+            .into_iter()
+            .filter_map(move |d| self.offset(pos, d))
+    }
+
+    pub fn neighbors8(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        DIRECTIONS8
+            .into_iter()
+            .filter_map(move |d| self.offset(pos, d))
+    }
+
+    /// # Panics
+    ///
+    /// If `row` is not on the grid.
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.cells[row * self.width..(row + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        (0..self.height).map(|r| self.row(r))
+    }
+
+    /// # Panics
+    ///
+    /// If `column` is not on the grid.
+    pub fn column(&self, column: usize) -> impl Iterator<Item = &T> {
+        assert!(column < self.width, "column {column} is not on the grid");
+        self.cells[column..].iter().step_by(self.width)
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(|c| self.column(c))
+    }
+
+    /// Every position, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = Pos> {
+        let width = self.width;
+        (0..self.height).flat_map(move |r| (0..width).map(move |c| (r, c)))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Pos, &T)> {
+        self.positions().zip(&self.cells)
+    }
+
+    /// The first position, row by row, whose cell matches.
+    pub fn position(&self, f: impl FnMut(&T) -> bool) -> Option<Pos> {
+        let i = self.cells.iter().position(f)?;
+        Some((i / self.width, i % self.width))
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    /// Draws one character per cell, every row ends with a new line.
+    pub fn render(&self, mut f: impl FnMut(&T) -> char) -> String {
+        let mut s = String::with_capacity((self.width + 1) * self.height);
+        for row in self.rows() {
+            s.extend(row.iter().map(&mut f));
+            s.push('\n');
+        }
+        s
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(height: usize, width: usize, fill: T) -> Self {
+        Grid {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+
+    /// Like [`Grid::parse`], but rows shorter than the longest one are filled with `pad`.
+    pub fn parse_padded(input: &str, pad: T, f: impl FnMut(char) -> Result<T>) -> Result<Self> {
+        let mut rows = parse_rows(input, f)?;
+        let width = rows.iter().map(|r| r.len()).max().unwrap_or(0);
+        for row in &mut rows {
+            row.resize(width, pad.clone());
+        }
+        Grid::from_rows(rows)
+    }
+
+    /// A larger grid with `margin` cells of `fill` on every side, the cell at `pos` moves to
+    /// `(pos.0 + margin, pos.1 + margin)`.
+    pub fn padded(&self, margin: usize, fill: T) -> Self {
+        let mut grid = Grid::new(self.height + 2 * margin, self.width + 2 * margin, fill);
+        for ((r, c), cell) in self.iter() {
+            grid[(r + margin, c + margin)] = cell.clone();
+        }
+        grid
+    }
+}
+
+fn parse_rows<T>(input: &str, mut f: impl FnMut(char) -> Result<T>) -> Result<Vec<Vec<T>>> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| {
+            line.chars()
+                .enumerate()
+                .map(|(j, c)| f(c).at(i + 1, j + 1))
+                .collect()
+        })
+        .collect()
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &T {
+        match self.get(pos) {
+            Some(cell) => cell,
+            None => panic!("{pos:?} is not on a {}x{} grid", self.height, self.width),
+        }
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut T {
+        let (height, width) = (self.height, self.width);
+        match self.get_mut(pos) {
+            Some(cell) => cell,
+            None => panic!("{pos:?} is not on a {height}x{width} grid"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn digits(input: &str) -> Result<Grid<u32>> {
+        Grid::parse(input, |c| match c.to_digit(10) {
+            Some(d) => Ok(d),
+            None => parse_err!("{c:?} is not a digit"),
+        })
+    }
+
+    #[test]
+    fn parse_and_render() {
+        let grid = digits("123\n456\n").unwrap();
+        assert_eq!((grid.height(), grid.width()), (2, 3));
+        assert_eq!(grid[(1, 0)], 4);
+        assert_eq!(
+            grid.render(|d| char::from_digit(*d, 10).unwrap()),
+            "123\n456\n"
+        );
+
+        let e = digits("12\n4x\n").unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "parse error at line 2, column 2: 'x' is not a digit"
+        );
+        let e = digits("12\n456\n").unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "parse error at line 2: the grid is not a rectangle"
+        );
+
+        let grid = Grid::parse_padded("#\n.#\n", ' ', Ok).unwrap();
+        assert_eq!(grid.render(|&c| c), "# \n.#\n");
+        let grid = grid.padded(1, '~');
+        assert_eq!(grid.render(|&c| c), "~~~~\n~# ~\n~.#~\n~~~~\n");
+    }
+
+    #[test]
+    fn neighbors() {
+        let grid = Grid::new(3, 4, 0);
+        assert_eq!(
+            grid.neighbors4((0, 0)).collect::<Vec<_>>(),
+            vec![(0, 1), (1, 0)]
+        );
+        assert_eq!(grid.neighbors4((1, 1)).count(), 4);
+        assert_eq!(grid.neighbors8((1, 1)).count(), 8);
+        assert_eq!(grid.neighbors8((2, 3)).count(), 3);
+        assert_eq!(grid.offset((2, 3), (-2, -3)), Some((0, 0)));
+        assert_eq!(grid.offset((2, 3), (1, 0)), None);
+        assert_eq!(grid.get_signed((-1, 0)), None);
+        assert_eq!(grid.get_signed((2, 3)), Some(&0));
+    }
+
+    #[test]
+    fn rows_and_columns() {
+        let grid = digits("123\n456\n").unwrap();
+        let rows: Vec<&[u32]> = grid.rows().collect();
+        assert_eq!(rows, vec![&[1, 2, 3][..], &[4, 5, 6][..]]);
+        let columns: Vec<Vec<u32>> = grid.columns().map(|c| c.copied().collect()).collect();
+        assert_eq!(columns, vec![vec![1, 4], vec![2, 5], vec![3, 6]]);
+        assert_eq!(grid.position(|&d| d == 5), Some((1, 1)));
+        assert_eq!(grid.iter().nth(4), Some(((1, 1), &5)));
+        assert_eq!(grid.map(|d| d * 2)[(0, 2)], 6);
+    }
+}
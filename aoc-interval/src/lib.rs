@@ -0,0 +1,270 @@
+//! Sets of integers kept as sorted intervals, shared by the days covering a line with ranges:
+//! the sections of Day 4 and the rows of Day 15.
+//!
+//! An interval `(start, end)` holds every integer from `start` to `end`, both included, and is
+//! empty when `start > end`. The bounds must stay away from the limits of `i64`, one past each
+//! end of an interval is computed.
+
+use std::fmt::{self, Display};
+
+pub type Interval = (i64, i64);
+
+/// Disjoint intervals sorted by their start, with at least one integer between two of them.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IntervalSet {
+    intervals: Vec<Interval>,
+}
+
+impl IntervalSet {
+    pub fn new() -> Self {
+        IntervalSet::default()
+    }
+
+    pub fn intervals(&self) -> &[Interval] {
+        &self.intervals
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// Adds `interval`, merging it with the intervals it overlaps or touches.
+    ///
+    /// Returns how many of its integers were already in the set.
+    pub fn insert(&mut self, (start, end): Interval) -> i64 {
+        if start > end {
+            return 0;
+        }
+        // intervals inserted by their start only ever meet the last one
+        match self.intervals.last_mut() {
+            Some(last) if last.1 + 1 < start => {
+                self.intervals.push((start, end));
+                return 0;
+            }
+            Some(last) if last.0 <= start => {
+                let covered = (last.1.min(end) - start + 1).max(0);
+                last.1 = last.1.max(end);
+                return covered;
+            }
+            None => {
+                self.intervals.push((start, end));
+                return 0;
+            }
+            _ => {}
+        }
+        // the intervals from `first` to `last` excluded overlap or touch the new one
+        let first = self.intervals.partition_point(|&(_, e)| e + 1 < start);
+        let last = self.intervals.partition_point(|&(s, _)| s <= end + 1);
+        let touched = &self.intervals[first..last];
+        let covered = touched
+            .iter()
+            .map(|&(s, e)| (e.min(end) - s.max(start) + 1).max(0))
+            .sum();
+        match touched {
+            [] => self.intervals.insert(first, (start, end)),
+            [(s, e)] => self.intervals[first] = (start.min(*s), end.max(*e)),
+            [(s, _), .., (_, e)] => {
+                let merged = (start.min(*s), end.max(*e));
+                self.intervals.splice(first..last, [merged]);
+            }
+        }
+        covered
+    }
+
+    pub fn contains(&self, x: i64) -> bool {
+        let i = self.intervals.partition_point(|&(_, e)| e < x);
+        self.intervals.get(i).is_some_and(|&(s, _)| s <= x)
+    }
+
+    /// How many integers are in the set.
+    pub fn coverage(&self) -> i64 {
+        self.intervals.iter().map(|&(s, e)| e - s + 1).sum()
+    }
+
+    pub fn union(&self, other: &IntervalSet) -> IntervalSet {
+        let mut union = self.clone();
+        for &interval in &other.intervals {
+            union.insert(interval);
+        }
+        union
+    }
+
+    pub fn intersection(&self, other: &IntervalSet) -> IntervalSet {
+        let mut intervals = vec![];
+        let (mut i, mut j) = (0, 0);
+        while let (Some(&(s1, e1)), Some(&(s2, e2))) =
+            (self.intervals.get(i), other.intervals.get(j))
+        {
+            let (s, e) = (s1.max(s2), e1.min(e2));
+            if s <= e {
+                intervals.push((s, e));
+            }
+            // the interval ending first can not meet anything else
+            if e1 < e2 {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        IntervalSet { intervals }
+    }
+
+    /// The integers of `self` that are not in `other`.
+    pub fn difference(&self, other: &IntervalSet) -> IntervalSet {
+        let mut intervals = vec![];
+        let mut j = 0;
+        for &(mut start, end) in &self.intervals {
+            while let Some(&(s, e)) = other.intervals.get(j) {
+                if e < start {
+                    j += 1;
+                    continue;
+                }
+                if s > end {
+                    break;
+                }
+                if s > start {
+                    intervals.push((start, s - 1));
+                }
+                start = e + 1;
+                if e > end {
+                    break;
+                }
+                j += 1;
+            }
+            if start <= end {
+                intervals.push((start, end));
+            }
+        }
+        IntervalSet { intervals }
+    }
+
+    /// The integers missing between the first and the last interval of the set.
+    pub fn gaps(&self) -> impl Iterator<Item = Interval> + '_ {
+        self.intervals.windows(2).map(|w| (w[0].1 + 1, w[1].0 - 1))
+    }
+
+    /// The integers of `bounds` missing from the set.
+    pub fn gaps_within(&self, bounds: Interval) -> IntervalSet {
+        IntervalSet::from_iter([bounds]).difference(self)
+    }
+}
+
+impl FromIterator<Interval> for IntervalSet {
+    fn from_iter<I: IntoIterator<Item = Interval>>(iter: I) -> Self {
+        let mut raw: Vec<Interval> = iter.into_iter().filter(|&(s, e)| s <= e).collect();
+        raw.sort_unstable();
+        let mut intervals: Vec<Interval> = Vec::with_capacity(raw.len());
+        for (start, end) in raw {
+            match intervals.last_mut() {
+                Some(last) if start <= last.1 + 1 => last.1 = last.1.max(end),
+                _ => intervals.push((start, end)),
+            }
+        }
+        IntervalSet { intervals }
+    }
+}
+
+/// `1..=3, 5, 8..=9`
+impl Display for IntervalSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, &(s, e)) in self.intervals.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            if s == e {
+                write!(f, "{s}")?;
+            } else {
+                write!(f, "{s}..={e}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The integers in the most of `intervals`, with how many intervals hold them.
+///
+/// `None` when every interval is empty.
+pub fn deepest(intervals: impl IntoIterator<Item = Interval>) -> Option<(IntervalSet, usize)> {
+    // +1 where an interval starts, -1 just past where it ends
+    let mut events: Vec<(i64, i64)> = intervals
+        .into_iter()
+        .filter(|&(s, e)| s <= e)
+        .flat_map(|(s, e)| [(s, 1), (e + 1, -1)])
+        .collect();
+    events.sort_unstable();
+    let mut depth = 0;
+    let mut deepest: Option<(Vec<Interval>, i64)> = None;
+    let mut groups = events.chunk_by(|e1, e2| e1.0 == e2.0).peekable();
+    while let Some(group) = groups.next() {
+        depth += group.iter().map(|&(_, change)| change).sum::<i64>();
+        // the depth holds until the events at the next integer
+        let (x, Some(&[(next, _), ..])) = (group[0].0, groups.peek()) else {
+            break;
+        };
+        if depth == 0 {
+            continue;
+        }
+        match &mut deepest {
+            Some((_, max)) if depth < *max => {}
+            Some((intervals, max)) if depth == *max => intervals.push((x, next - 1)),
+            _ => deepest = Some((vec![(x, next - 1)], depth)),
+        }
+    }
+    deepest.map(|(intervals, depth)| (intervals.into_iter().collect(), depth as usize))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(intervals: &[Interval]) -> IntervalSet {
+        intervals.iter().copied().collect()
+    }
+
+    #[test]
+    fn test_merge() {
+        let s = set(&[(5, 7), (1, 2), (3, 3), (10, 12), (11, 11), (20, 19)]);
+        assert_eq!(s.intervals(), [(1, 3), (5, 7), (10, 12)]);
+        assert_eq!(s.coverage(), 9);
+        assert!(s.contains(6) && !s.contains(4) && !s.contains(13));
+        assert_eq!(s.to_string(), "1..=3, 5..=7, 10..=12");
+
+        let mut s = s;
+        assert_eq!(s.insert((2, 11)), 7);
+        assert_eq!(s.intervals(), [(1, 12)]);
+        assert_eq!(s.insert((14, 14)), 0);
+        assert_eq!(s.insert((13, 13)), 0);
+        assert_eq!(s.intervals(), [(1, 14)]);
+    }
+
+    #[test]
+    fn test_operations() {
+        let a = set(&[(1, 5), (10, 15), (20, 20)]);
+        let b = set(&[(4, 11), (15, 30)]);
+        assert_eq!(a.union(&b).intervals(), [(1, 30)]);
+        assert_eq!(
+            a.intersection(&b).intervals(),
+            [(4, 5), (10, 11), (15, 15), (20, 20)]
+        );
+        assert_eq!(a.difference(&b).intervals(), [(1, 3), (12, 14)]);
+        assert_eq!(b.difference(&a).intervals(), [(6, 9), (16, 19), (21, 30)]);
+        assert_eq!(a.gaps().collect::<Vec<_>>(), [(6, 9), (16, 19)]);
+        assert_eq!(
+            a.gaps_within((0, 22)).to_string(),
+            "0, 6..=9, 16..=19, 21..=22"
+        );
+        assert!(IntervalSet::new().gaps_within((3, 2)).is_empty());
+    }
+
+    #[test]
+    fn test_deepest() {
+        let (sections, depth) = deepest([(1, 5), (3, 8), (4, 4), (7, 9), (12, 12)]).unwrap();
+        assert_eq!(depth, 3);
+        assert_eq!(sections.intervals(), [(4, 4)]);
+        let (sections, depth) = deepest([(1, 2), (2, 3), (5, 6), (6, 8)]).unwrap();
+        assert_eq!(depth, 2);
+        assert_eq!(sections.to_string(), "2, 6");
+        assert_eq!(deepest([(1, 1)]).unwrap().1, 1);
+        assert_eq!(deepest([(2, 1)]), None);
+    }
+}
@@ -0,0 +1,241 @@
+//! Searches over a graph given by a neighbor function, shared by the days looking for a way out.
+//!
+//! Every search takes several starts, and the searches looking for a goal return the path they
+//! found, from the start it came from to the goal.
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+use std::ops::Add;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Path<N, C = usize> {
+    pub cost: C,
+    /// From the start to the goal, both included.
+    pub nodes: Vec<N>,
+}
+
+/// The nodes seen by a search, with the node each one was reached from.
+struct Visited<N> {
+    ids: HashMap<N, usize>,
+    nodes: Vec<N>,
+    parents: Vec<Option<usize>>,
+}
+
+impl<N: Clone + Eq + Hash> Visited<N> {
+    fn new() -> Self {
+        Visited {
+            ids: HashMap::new(),
+            nodes: vec![],
+            parents: vec![],
+        }
+    }
+
+    fn id(&self, node: &N) -> Option<usize> {
+        self.ids.get(node).copied()
+    }
+
+    fn insert(&mut self, node: N, parent: Option<usize>) -> usize {
+        let id = self.nodes.len();
+        self.ids.insert(node.clone(), id);
+        self.nodes.push(node);
+        self.parents.push(parent);
+        id
+    }
+
+    fn path(&self, mut id: usize) -> Vec<N> {
+        let mut path = vec![self.nodes[id].clone()];
+        while let Some(parent) = self.parents[id] {
+            path.push(self.nodes[parent].clone());
+            id = parent;
+        }
+        path.reverse();
+        path
+    }
+}
+
+/// Breadth first search, every step costs 1.
+pub fn bfs<N, I>(
+    starts: impl IntoIterator<Item = N>,
+    mut neighbors: impl FnMut(&N) -> I,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Option<Path<N>>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut visited = Visited::new();
+    let mut queue = VecDeque::new();
+    for start in starts {
+        if visited.id(&start).is_none() {
+            queue.push_back((visited.insert(start, None), 0));
+        }
+    }
+    while let Some((id, cost)) = queue.pop_front() {
+        if is_goal(&visited.nodes[id]) {
+            return Some(Path {
+                cost,
+                nodes: visited.path(id),
+            });
+        }
+        for next in neighbors(&visited.nodes[id]) {
+            if visited.id(&next).is_none() {
+                queue.push_back((visited.insert(next, Some(id)), cost + 1));
+            }
+        }
+    }
+    None
+}
+
+/// Dijkstra's algorithm, `neighbors` gives the cost of every step, which must not be negative.
+pub fn dijkstra<N, C, I>(
+    starts: impl IntoIterator<Item = N>,
+    neighbors: impl FnMut(&N) -> I,
+    is_goal: impl FnMut(&N) -> bool,
+) -> Option<Path<N, C>>
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (N, C)>,
+{
+    astar(starts, neighbors, |_| C::default(), is_goal)
+}
+
+/// A* search, the path is the cheapest one as long as `heuristic` never overestimates the cost
+/// left to a goal.
+pub fn astar<N, C, I>(
+    starts: impl IntoIterator<Item = N>,
+    mut neighbors: impl FnMut(&N) -> I,
+    mut heuristic: impl FnMut(&N) -> C,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Option<Path<N, C>>
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (N, C)>,
+{
+    let mut visited = Visited::new();
+    let mut costs = vec![];
+    let mut heap = BinaryHeap::new();
+    for start in starts {
+        if visited.id(&start).is_none() {
+            let estimate = heuristic(&start);
+            heap.push(Reverse((
+                estimate,
+                C::default(),
+                visited.insert(start, None),
+            )));
+            costs.push(C::default());
+        }
+    }
+    while let Some(Reverse((_, cost, id))) = heap.pop() {
+        if cost > costs[id] {
+            // reached again with a lower cost after it was pushed
+            continue;
+        }
+        if is_goal(&visited.nodes[id]) {
+            return Some(Path {
+                cost,
+                nodes: visited.path(id),
+            });
+        }
+        for (next, step) in neighbors(&visited.nodes[id]) {
+            let next_cost = cost + step;
+            let next_id = match visited.id(&next) {
+                Some(next_id) if costs[next_id] <= next_cost => continue,
+                Some(next_id) => {
+                    costs[next_id] = next_cost;
+                    visited.parents[next_id] = Some(id);
+                    next_id
+                }
+                None => {
+                    costs.push(next_cost);
+                    visited.insert(next.clone(), Some(id))
+                }
+            };
+            heap.push(Reverse((next_cost + heuristic(&next), next_cost, next_id)));
+        }
+    }
+    None
+}
+
+/// Every node reachable from the starts, the starts included.
+pub fn flood_fill<N, I>(
+    starts: impl IntoIterator<Item = N>,
+    mut neighbors: impl FnMut(&N) -> I,
+) -> HashSet<N>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut stack: Vec<N> = starts.into_iter().collect();
+    let mut reached: HashSet<N> = stack.iter().cloned().collect();
+    while let Some(cur) = stack.pop() {
+        for next in neighbors(&cur) {
+            if reached.insert(next.clone()) {
+                stack.push(next);
+            }
+        }
+    }
+    reached
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A line of nodes from 0 to 9, with a shortcut from 2 to 7 that costs 4.
+    fn line(&n: &u32) -> Vec<(u32, u32)> {
+        let mut next = vec![];
+        if n > 0 {
+            next.push((n - 1, 1));
+        }
+        if n < 9 {
+            next.push((n + 1, 1));
+        }
+        if n == 2 {
+            next.push((7, 4));
+        }
+        next
+    }
+
+    fn steps(n: &u32) -> Vec<u32> {
+        line(n).into_iter().map(|(n, _)| n).collect()
+    }
+
+    #[test]
+    fn test_bfs() {
+        let path = bfs([0], steps, |&n| n == 8).unwrap();
+        assert_eq!(path.cost, 4);
+        assert_eq!(path.nodes, vec![0, 1, 2, 7, 8]);
+
+        // the closest start wins
+        let path = bfs([0, 9], steps, |&n| n == 8).unwrap();
+        assert_eq!(path.nodes, vec![9, 8]);
+
+        assert_eq!(bfs([3], steps, |&n| n == 3).unwrap().cost, 0);
+        assert_eq!(bfs([0], steps, |&n| n == 10), None);
+    }
+
+    #[test]
+    fn test_dijkstra_and_astar() {
+        let path = dijkstra([0], line, |&n| n == 8).unwrap();
+        assert_eq!(path.cost, 7);
+        assert_eq!(path.nodes, vec![0, 1, 2, 7, 8]);
+        let path = dijkstra([0], line, |&n| n == 6).unwrap();
+        assert_eq!(path.cost, 6);
+        assert_eq!(path.nodes, (0..=6).collect::<Vec<_>>());
+
+        let path = astar([0], line, |&n| 8u32.abs_diff(n) / 2, |&n| n == 8).unwrap();
+        assert_eq!(path.cost, 7);
+        assert_eq!(astar([0], line, |_| 0, |&n| n == 10), None);
+    }
+
+    #[test]
+    fn test_flood_fill() {
+        let reached = flood_fill([4], |&n| steps(&n).into_iter().filter(|&n| n != 2));
+        let mut reached: Vec<u32> = reached.into_iter().collect();
+        reached.sort();
+        assert_eq!(reached, vec![3, 4, 5, 6, 7, 8, 9]);
+    }
+}
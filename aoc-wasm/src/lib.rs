@@ -0,0 +1,100 @@
+//! The solvers compiled to WebAssembly for the browser playground in `www`, see `./playground`.
+//!
+//! Errors are returned as their message, which JavaScript gets as a thrown string.
+
+use aoc::days;
+use aoc_common::Screen;
+use wasm_bindgen::prelude::*;
+
+/// More frames would take too much of the browser's memory, the animation stops there.
+pub const MAX_FRAMES: usize = 20000;
+
+fn check(day: u8, part: u8) -> Result<(), String> {
+    if !(1..=25).contains(&day) {
+        return Err(format!("there is no day {day}, the days go from 1 to 25"));
+    }
+    if !(1..=2).contains(&part) {
+        return Err(format!("there is no part {part}, a day has parts 1 and 2"));
+    }
+    Ok(())
+}
+
+/// Solves one part of a day and returns the answer as it is printed by `aoc run`.
+#[wasm_bindgen]
+pub fn solve(day: u8, part: u8, input: &str) -> Result<String, String> {
+    check(day, part)?;
+    let outcomes = days::run(day, input, Some(part), false).map_err(|e| e.to_string())?;
+    match outcomes.into_iter().next() {
+        Some(outcome) => outcome
+            .answer
+            .map(|answer| answer.to_string())
+            .map_err(|e| e.to_string()),
+        None => Err(format!("day {day} has no part {part}")),
+    }
+}
+
+/// Whether the part of a day has an animation to play with [`frames`].
+#[wasm_bindgen]
+pub fn animated(day: u8, part: u8) -> bool {
+    check(day, part).is_ok() && days::has_animation(day, part)
+}
+
+/// Keeps one frame out of `every`, until there are [`MAX_FRAMES`] of them.
+struct Frames {
+    every: usize,
+    seen: usize,
+    frames: Vec<String>,
+}
+
+impl Screen for Frames {
+    fn show(&mut self, frame: &str) -> bool {
+        if self.seen.is_multiple_of(self.every) {
+            self.frames.push(frame.to_string());
+        }
+        self.seen += 1;
+        self.frames.len() < MAX_FRAMES
+    }
+}
+
+/// The frames of the animation of one part of a day, one frame out of `every`.
+///
+/// Empty when the part has no animation.
+#[wasm_bindgen]
+pub fn frames(day: u8, part: u8, input: &str, every: usize) -> Result<Vec<String>, String> {
+    check(day, part)?;
+    let mut frames = Frames {
+        every: every.max(1),
+        seen: 0,
+        frames: vec![],
+    };
+    days::animate(day, input, Some(part), &mut frames).map_err(|e| e.to_string())?;
+    Ok(frames.frames)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn solve_examples() {
+        let input = "1000\n2000\n3000\n\n4000\n\n5000\n6000\n\n7000\n8000\n9000\n\n10000";
+        assert_eq!(solve(1, 1, input).unwrap(), "24000");
+        assert_eq!(solve(1, 2, input).unwrap(), "45000");
+        assert!(solve(26, 1, input).unwrap_err().contains("no day 26"));
+        assert!(solve(1, 3, input).unwrap_err().contains("no part 3"));
+        assert!(solve(1, 1, "elf").unwrap_err().starts_with("parse error"));
+    }
+
+    #[test]
+    fn example_frames() {
+        let input = "498,4 -> 498,6 -> 496,6\n503,4 -> 502,4 -> 502,9 -> 494,9";
+        assert!(animated(14, 1));
+        assert!(!animated(1, 1));
+        let all = frames(14, 1, input, 1).unwrap();
+        let some = frames(14, 1, input, 10).unwrap();
+        assert!(!all.is_empty());
+        assert_eq!(some.len(), all.len().div_ceil(10));
+        assert_eq!(some[1], all[10]);
+        assert!(frames(1, 1, "1000", 1).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,129 @@
+// Runs the solvers built by `./playground` in the page, nothing leaves the browser.
+
+import init, { solve, animated, frames } from "./pkg/aoc_wasm.js";
+
+const $ = (id) => document.getElementById(id);
+
+// the animation being played
+const player = { frames: [], title: "", frame: 0, fps: 30, paused: false, timer: null };
+
+function parts() {
+  const part = Number($("part").value);
+  return part === 0 ? [1, 2] : [part];
+}
+
+// lets the page paint the message before the solver blocks it
+function later(message, f) {
+  $("answers").textContent = message;
+  $("answers").className = "";
+  setTimeout(f, 20);
+}
+
+function runSolve() {
+  const day = Number($("day").value);
+  const input = $("input").value;
+  later("Solving...", () => {
+    const lines = [];
+    let failed = false;
+    for (const part of parts()) {
+      const start = performance.now();
+      try {
+        const answer = solve(day, part, input);
+        const elapsed = (performance.now() - start).toFixed(1);
+        lines.push(answer.includes("\n") ? `Part${part}: \n${answer}` : `Part${part}: ${answer}`);
+        lines.push(`> Time elapsed is: ${elapsed}ms`);
+      } catch (e) {
+        lines.push(`Part${part} failed: ${e}`);
+        failed = true;
+      }
+    }
+    $("answers").textContent = lines.join("\n");
+    $("answers").className = failed ? "error" : "";
+  });
+}
+
+function stop() {
+  clearTimeout(player.timer);
+  player.timer = null;
+}
+
+function draw() {
+  $("screen").textContent = player.frames[player.frame] ?? "";
+  $("title").textContent =
+    `${player.title}, frame ${player.frame + 1}/${player.frames.length}` +
+    (player.paused ? " (paused)" : "");
+}
+
+function tick() {
+  draw();
+  if (player.paused || player.frame + 1 >= player.frames.length) {
+    player.timer = null;
+    return;
+  }
+  player.frame += 1;
+  player.timer = setTimeout(tick, 1000 / player.fps);
+}
+
+function runVisualize() {
+  stop();
+  const day = Number($("day").value);
+  const input = $("input").value;
+  const every = Math.max(1, Number($("every").value) || 1);
+  later("Simulating...", () => {
+    const all = [];
+    try {
+      for (const part of parts().filter((part) => animated(day, part))) {
+        all.push(...frames(day, part, input, every));
+      }
+    } catch (e) {
+      $("answers").textContent = `Day ${String(day).padStart(2, "0")} failed: ${e}`;
+      $("answers").className = "error";
+      return;
+    }
+    $("answers").textContent = "";
+    Object.assign(player, {
+      frames: all,
+      title: `Day ${String(day).padStart(2, "0")}`,
+      frame: 0,
+      fps: Math.max(1, Number($("fps").value) || 30),
+      paused: false,
+    });
+    tick();
+  });
+}
+
+function updateVisualize() {
+  const day = Number($("day").value);
+  $("visualize").disabled = !parts().some((part) => animated(day, part));
+}
+
+document.addEventListener("keydown", (e) => {
+  if (e.target === $("input") || player.frames.length === 0) {
+    return;
+  }
+  if (e.key === " ") {
+    player.paused = !player.paused;
+    e.preventDefault();
+  } else if (e.key === "n") {
+    player.paused = true;
+    player.frame = Math.min(player.frame + 1, player.frames.length - 1);
+  } else if (e.key === "+") {
+    player.fps = Math.min(player.fps * 2, 1000);
+  } else if (e.key === "-") {
+    player.fps = Math.max(player.fps / 2, 1);
+  } else {
+    return;
+  }
+  stop();
+  tick();
+});
+
+await init();
+for (let day = 1; day <= 25; day++) {
+  $("day").add(new Option(String(day).padStart(2, "0"), day));
+}
+$("day").addEventListener("change", updateVisualize);
+$("part").addEventListener("change", updateVisualize);
+$("solve").addEventListener("click", runSolve);
+$("visualize").addEventListener("click", runVisualize);
+updateVisualize();
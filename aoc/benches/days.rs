@@ -0,0 +1,100 @@
+//! Benchmarks every day on its puzzle input: parsing, each part on the parsed input, and every
+//! variant of a part next to the default implementation, all of them starting from the raw input.
+//!
+//! Days without an `aocXX/input/input.txt` or a fetched input are skipped. Set `AOC_DAYS` to a comma separated list
+//! of days to only benchmark those.
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use aoc::days::{with_day, DayVisitor};
+use aoc::input::{cache_dir, cached_input};
+use aoc_common::Solution;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+struct Bench<'a> {
+    c: &'a mut Criterion,
+    input: &'a str,
+}
+
+impl DayVisitor for Bench<'_> {
+    type Output = ();
+
+    fn visit<S: Solution>(self, day: u8) {
+        let input = self.input;
+        let parsed = match S::parse(input) {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                eprintln!("skip day {day:02}: {e}");
+                return;
+            }
+        };
+
+        let mut group = self.c.benchmark_group(format!("day{day:02}"));
+        group.bench_function("parse", |b| b.iter(|| S::parse(black_box(input))));
+        group.bench_function("part1", |b| b.iter(|| S::part1(black_box(&parsed))));
+        group.bench_function("part2", |b| b.iter(|| S::part2(black_box(&parsed))));
+        group.finish();
+
+        let variants = S::variants();
+        for part in [1, 2] {
+            if !variants.iter().any(|v| v.part == part) {
+                continue;
+            }
+            let solve = if part == 1 { S::part1 } else { S::part2 };
+            let mut group = self.c.benchmark_group(format!("day{day:02}/part{part}"));
+            group.bench_function("default", |b| {
+                b.iter(|| S::parse(black_box(input)).and_then(|parsed| solve(&parsed)))
+            });
+            for variant in variants.iter().filter(|v| v.part == part) {
+                group.bench_function(variant.name, |b| {
+                    b.iter(|| (variant.solve)(black_box(input)))
+                });
+            }
+            group.finish();
+        }
+    }
+}
+
+fn selected_days() -> Vec<u8> {
+    match env::var("AOC_DAYS") {
+        Ok(days) => days
+            .split(',')
+            .map(|d| {
+                d.trim()
+                    .parse()
+                    .expect("AOC_DAYS is a comma separated list of days")
+            })
+            .collect(),
+        Err(_) => (1..=25).collect(),
+    }
+}
+
+fn days(c: &mut Criterion) {
+    let root = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("..");
+    for day in selected_days() {
+        let mut path = root.join(format!("aoc{day:02}/input/input.txt"));
+        if let Some(cached) = cache_dir().map(|dir| cached_input(&dir, day)) {
+            if !path.exists() && cached.exists() {
+                path = cached;
+            }
+        }
+        match fs::read_to_string(&path) {
+            Ok(input) => with_day(day, Bench { c, input: &input }),
+            Err(e) => eprintln!("skip day {day:02}: can not read {}: {e}", path.display()),
+        }
+    }
+}
+
+criterion_group! {
+    name = benches;
+    // some days take seconds per run, keep the whole suite in minutes
+    config = Criterion::default()
+        .sample_size(10)
+        .warm_up_time(Duration::from_millis(500))
+        .measurement_time(Duration::from_secs(2));
+    targets = days
+}
+criterion_main!(benches);
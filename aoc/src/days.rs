@@ -0,0 +1,273 @@
+use std::time::Duration;
+
+use aoc_common::{measured, timed, AllocStats, Answer, Generator, Part, Result, Screen, Solution};
+
+/// Something to do with the solver of one day, whatever its input type is.
+pub trait DayVisitor {
+    type Output;
+
+    fn visit<S: Solution>(self, day: u8) -> Self::Output;
+}
+
+/// Calls `visitor` with the solver of `day`.
+///
+/// # Panics
+///
+/// If `day` is not between 1 and 25.
+pub fn with_day<V: DayVisitor>(day: u8, visitor: V) -> V::Output {
+    match day {
+        1 => visitor.visit::<aoc01::Day01>(day),
+        2 => visitor.visit::<aoc02::Day02>(day),
+        3 => visitor.visit::<aoc03::Day03>(day),
+        4 => visitor.visit::<aoc04::Day04>(day),
+        5 => visitor.visit::<aoc05::Day05>(day),
+        6 => visitor.visit::<aoc06::Day06>(day),
+        7 => visitor.visit::<aoc07::Day07>(day),
+        8 => visitor.visit::<aoc08::Day08>(day),
+        9 => visitor.visit::<aoc09::Day09>(day),
+        10 => visitor.visit::<aoc10::Day10>(day),
+        11 => visitor.visit::<aoc11::Day11>(day),
+        12 => visitor.visit::<aoc12::Day12>(day),
+        13 => visitor.visit::<aoc13::Day13>(day),
+        14 => visitor.visit::<aoc14::Day14>(day),
+        15 => visitor.visit::<aoc15::Day15>(day),
+        16 => visitor.visit::<aoc16::Day16>(day),
+        17 => visitor.visit::<aoc17::Day17>(day),
+        18 => visitor.visit::<aoc18::Day18>(day),
+        19 => visitor.visit::<aoc19::Day19>(day),
+        20 => visitor.visit::<aoc20::Day20>(day),
+        21 => visitor.visit::<aoc21::Day21>(day),
+        22 => visitor.visit::<aoc22::Day22>(day),
+        23 => visitor.visit::<aoc23::Day23>(day),
+        24 => visitor.visit::<aoc24::Day24>(day),
+        25 => visitor.visit::<aoc25::Day25>(day),
+        _ => panic!("there is no solver for day {day}"),
+    }
+}
+
+/// The answer of one part, or the error it failed with, and the time it took.
+pub struct Outcome {
+    pub part: u8,
+    /// The alternative implementation that answered, `None` for the one of the trait.
+    ///
+    /// The time and memory of a variant include parsing the input, as a variant starts from the
+    /// text.
+    pub variant: Option<&'static str>,
+    pub answer: Result<Answer>,
+    pub elapsed: Duration,
+    /// What the part allocated, only counted with the `alloc-stats` feature.
+    pub memory: Option<AllocStats>,
+}
+
+/// Times `f`, and counts what it allocates with the `alloc-stats` feature.
+fn profiled<T>(f: impl FnOnce() -> T) -> (T, Duration, Option<AllocStats>) {
+    if cfg!(feature = "alloc-stats") {
+        let ((result, elapsed), memory) = measured(|| timed(f));
+        (result, elapsed, Some(memory))
+    } else {
+        let (result, elapsed) = timed(f);
+        (result, elapsed, None)
+    }
+}
+
+struct Solve<'a> {
+    input: &'a str,
+    part: Option<u8>,
+    variants: bool,
+}
+
+impl DayVisitor for Solve<'_> {
+    type Output = Result<Vec<Outcome>>;
+
+    fn visit<S: Solution>(self, _day: u8) -> Self::Output {
+        let input = S::parse(self.input)?;
+        let parts: [(u8, Part<S::Input>); 2] = [(1, S::part1), (2, S::part2)];
+        let mut outcomes: Vec<_> = parts
+            .into_iter()
+            .filter(|&(n, _)| self.part.is_none_or(|p| p == n))
+            .map(|(n, solve_part)| {
+                let (answer, elapsed, memory) = profiled(|| solve_part(&input));
+                Outcome {
+                    part: n,
+                    variant: None,
+                    answer,
+                    elapsed,
+                    memory,
+                }
+            })
+            .collect();
+        if self.variants {
+            let variants = S::variants()
+                .into_iter()
+                .filter(|v| self.part.is_none_or(|p| p == v.part))
+                .map(|v| {
+                    let (answer, elapsed, memory) = profiled(|| (v.solve)(self.input));
+                    Outcome {
+                        part: v.part,
+                        variant: Some(v.name),
+                        answer,
+                        elapsed,
+                        memory,
+                    }
+                });
+            outcomes.extend(variants);
+            outcomes.sort_by_key(|o| o.part);
+        }
+        Ok(outcomes)
+    }
+}
+
+/// Solves the selected parts of `day`, failing only when the input can not be parsed.
+///
+/// With `variants`, the alternative implementations of the selected parts are run too, each
+/// after the implementation of the trait for the same part.
+pub fn run(day: u8, input: &str, part: Option<u8>, variants: bool) -> Result<Vec<Outcome>> {
+    with_day(
+        day,
+        Solve {
+            input,
+            part,
+            variants,
+        },
+    )
+}
+
+/// Calls `f` on every day of `days` and returns the results in the same order.
+///
+/// With the `parallel` feature the days run at the same time, so the time of each part includes
+/// waiting for the other days.
+pub fn map_days<T: Send>(days: &[u8], f: impl Fn(u8) -> T + Sync) -> Vec<T> {
+    #[cfg(feature = "parallel")]
+    {
+        use rayon::prelude::*;
+        days.par_iter().map(|&day| f(day)).collect()
+    }
+    #[cfg(not(feature = "parallel"))]
+    {
+        days.iter().map(|&day| f(day)).collect()
+    }
+}
+
+struct Animate<'a> {
+    input: &'a str,
+    part: Option<u8>,
+    screen: &'a mut dyn Screen,
+}
+
+impl DayVisitor for Animate<'_> {
+    type Output = Result<usize>;
+
+    fn visit<S: Solution>(self, _day: u8) -> Self::Output {
+        let animations: Vec<_> = S::animations()
+            .into_iter()
+            .filter(|a| self.part.is_none_or(|p| p == a.part))
+            .collect();
+        for animation in &animations {
+            self.screen.begin(animation);
+            (animation.animate)(self.input, self.screen)?;
+        }
+        Ok(animations.len())
+    }
+}
+
+/// Plays the animations of the selected parts of `day` on `screen`, returns how many there were.
+pub fn animate(day: u8, input: &str, part: Option<u8>, screen: &mut dyn Screen) -> Result<usize> {
+    with_day(
+        day,
+        Animate {
+            input,
+            part,
+            screen,
+        },
+    )
+}
+
+struct HasAnimation {
+    part: u8,
+}
+
+impl DayVisitor for HasAnimation {
+    type Output = bool;
+
+    fn visit<S: Solution>(self, _day: u8) -> Self::Output {
+        S::animations().iter().any(|a| a.part == self.part)
+    }
+}
+
+/// Whether `day` has an animation of `part`, without running it.
+pub fn has_animation(day: u8, part: u8) -> bool {
+    with_day(day, HasAnimation { part })
+}
+
+struct FindGenerator;
+
+impl DayVisitor for FindGenerator {
+    type Output = Option<Generator>;
+
+    fn visit<S: Solution>(self, _day: u8) -> Self::Output {
+        S::generator()
+    }
+}
+
+/// The generator of puzzle inputs of `day`, if it has one.
+pub fn generator(day: u8) -> Option<Generator> {
+    with_day(day, FindGenerator)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn map_days_keeps_the_order() {
+        let days: Vec<u8> = (1..=25).rev().collect();
+        assert_eq!(
+            map_days(&days, |day| day * 2),
+            days.iter().map(|d| d * 2).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn generated_inputs_are_solvable() {
+        for day in 1..=25 {
+            let Some(generator) = generator(day) else {
+                continue;
+            };
+            // the robots of day 19 take a minute per blueprint on a debug build, so its inputs are
+            // only parsed, part 0 is none of the parts
+            let part = if day == 19 { Some(0) } else { None };
+            for (size, seed) in [(1, 0), (5, 1), (9, 2)] {
+                let input = generator.generate(Some(size), seed, &[]).unwrap();
+                let again = generator.generate(Some(size), seed, &[]).unwrap();
+                assert_eq!(input, again, "day {day} is not deterministic");
+                let outcomes = run(day, &input, part, false)
+                    .unwrap_or_else(|e| panic!("day {day}, size {size}: {e}\n{input}"));
+                for outcome in outcomes {
+                    if let Err(e) = outcome.answer {
+                        panic!(
+                            "day {day}, size {size}, part {}: {e}\n{input}",
+                            outcome.part
+                        );
+                    }
+                }
+            }
+        }
+
+        // the smallest numbers of monkeys day 11 takes
+        let generator = generator(11).unwrap();
+        for monkeys in ["0", "1", "2", "3"] {
+            let knobs = [("monkeys".to_string(), monkeys.to_string())];
+            let input = generator
+                .generate(Some(5), 0, &knobs)
+                .unwrap_or_else(|e| panic!("day 11, {monkeys} monkeys: {e}"));
+            for outcome in run(11, &input, None, false).unwrap() {
+                if let Err(e) = outcome.answer {
+                    panic!(
+                        "day 11, {monkeys} monkeys, part {}: {e}\n{input}",
+                        outcome.part
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,382 @@
+use std::borrow::Cow;
+use std::fs::{self, File};
+use std::io::{self, BufWriter};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use aoc_common::{Animation, Error, Result, Screen};
+
+/// The colors of the characters the days draw with, any other character is magenta.
+const DEFAULT_PALETTE: &[(char, [u8; 3])] = &[
+    // the background, frames smaller than the image are padded with it
+    (' ', [0x00, 0x00, 0x00]),
+    ('.', [0x1a, 0x1a, 0x2e]),
+    ('#', [0x7f, 0x7f, 0x7f]),
+    ('o', [0xe0, 0xc0, 0x68]),
+    ('+', [0xff, 0xff, 0xff]),
+    ('@', [0xe8, 0x55, 0x3d]),
+    ('|', [0x5a, 0x5a, 0x5a]),
+    ('-', [0x5a, 0x5a, 0x5a]),
+    ('E', [0x3d, 0xdc, 0x84]),
+    ('^', [0x9a, 0xd0, 0xff]),
+    ('v', [0x9a, 0xd0, 0xff]),
+    ('<', [0x9a, 0xd0, 0xff]),
+    ('>', [0x9a, 0xd0, 0xff]),
+    ('2', [0x4a, 0x90, 0xd9]),
+    ('3', [0x4a, 0x90, 0xd9]),
+    ('4', [0x4a, 0x90, 0xd9]),
+];
+
+const OTHER: [u8; 3] = [0xff, 0x00, 0xff];
+
+/// The color of every character, `--palette` changes some of them like `#=40c040,o=ffffff`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Palette {
+    colors: Vec<(char, [u8; 3])>,
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Palette {
+            colors: DEFAULT_PALETTE.to_vec(),
+        }
+    }
+}
+
+impl FromStr for Palette {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, String> {
+        let mut palette = Palette::default();
+        for entry in s.split(',').filter(|e| !e.is_empty()) {
+            let mut chars = entry.chars();
+            let (Some(c), Some('='), color) = (chars.next(), chars.next(), chars.as_str()) else {
+                return Err(format!("{entry:?} is not like `c=rrggbb`"));
+            };
+            let rgb = u32::from_str_radix(color, 16)
+                .ok()
+                .filter(|_| color.len() == 6)
+                .ok_or_else(|| format!("{color:?} is not a color like `ff8000`"))?;
+            let [_, r, g, b] = rgb.to_be_bytes();
+            if let Some((_, old)) = palette.colors.iter_mut().find(|(p, _)| *p == c) {
+                *old = [r, g, b];
+            } else if palette.colors.len() < 255 {
+                palette.colors.push((c, [r, g, b]));
+            } else {
+                return Err("a palette has at most 255 colors".to_string());
+            }
+        }
+        Ok(palette)
+    }
+}
+
+impl Palette {
+    fn index(&self, c: char) -> u8 {
+        let i = self.colors.iter().position(|&(p, _)| p == c);
+        i.unwrap_or(self.colors.len()) as u8
+    }
+
+    fn background(&self) -> u8 {
+        self.index(' ')
+    }
+
+    /// The colors as `rgb` triplets, in the order of the indexes.
+    fn rgb(&self) -> Vec<u8> {
+        let mut rgb: Vec<u8> = self.colors.iter().flat_map(|(_, c)| *c).collect();
+        rgb.extend(OTHER);
+        rgb
+    }
+
+    /// The palette indexes of the pixels of `frame` on an image of `width` x `height` characters,
+    /// `scale` x `scale` pixels each.
+    fn rasterize(&self, frame: &str, (width, height): (usize, usize), scale: usize) -> Vec<u8> {
+        let mut pixels = vec![self.background(); width * height * scale * scale];
+        for (y, line) in frame.lines().take(height).enumerate() {
+            for (x, c) in line.chars().take(width).enumerate() {
+                let index = self.index(c);
+                for row in y * scale..(y + 1) * scale {
+                    let start = row * width * scale + x * scale;
+                    pixels[start..start + scale].fill(index);
+                }
+            }
+        }
+        pixels
+    }
+}
+
+/// The size of a frame in characters.
+fn frame_size(frame: &str) -> (usize, usize) {
+    let width = frame.lines().map(|l| l.chars().count()).max();
+    (width.unwrap_or(0), frame.lines().count())
+}
+
+/// Records the size of the largest frame of every animation, a GIF has one size for all frames.
+#[derive(Default)]
+pub struct Measure {
+    pub sizes: Vec<(usize, usize)>,
+}
+
+impl Screen for Measure {
+    fn begin(&mut self, _animation: &Animation) {
+        self.sizes.push((0, 0));
+    }
+
+    fn show(&mut self, frame: &str) -> bool {
+        let (width, height) = frame_size(frame);
+        if let Some(size) = self.sizes.last_mut() {
+            *size = (size.0.max(width), size.1.max(height));
+        }
+        true
+    }
+}
+
+/// Writes the frames of `aoc run --export` to images.
+///
+/// A path ending with `.gif` gets an animated GIF per animation, the sizes measured by
+/// [`Measure`] beforehand. Any other path is a directory with a PNG per frame.
+pub struct Export {
+    path: PathBuf,
+    palette: Palette,
+    scale: usize,
+    every: usize,
+    /// Hundredths of a second between the frames of a GIF.
+    delay: u16,
+    gif_sizes: Option<Vec<(usize, usize)>>,
+    /// The size of the frames of the GIF being written.
+    gif_size: (usize, usize),
+    animation: usize,
+    part: u8,
+    frame: usize,
+    gif: Option<gif::Encoder<BufWriter<File>>>,
+    written: Vec<PathBuf>,
+    error: Option<Error>,
+}
+
+impl Export {
+    pub fn new(path: &Path, palette: Palette, scale: u32, every: usize, fps: u32) -> Self {
+        Export {
+            path: path.to_path_buf(),
+            palette,
+            scale: scale as usize,
+            every,
+            delay: (100 / fps).max(2) as u16,
+            gif_sizes: None,
+            gif_size: (0, 0),
+            animation: 0,
+            part: 0,
+            frame: 0,
+            gif: None,
+            written: vec![],
+            error: None,
+        }
+    }
+
+    pub fn is_gif(&self) -> bool {
+        self.path.extension().is_some_and(|e| e == "gif")
+    }
+
+    pub fn with_gif_sizes(mut self, sizes: Vec<(usize, usize)>) -> Self {
+        self.gif_sizes = Some(sizes);
+        self
+    }
+
+    /// The files written, or the first error met.
+    pub fn finish(mut self) -> Result<Vec<PathBuf>> {
+        // the encoder writes the end of the GIF when dropped
+        self.gif = None;
+        match self.error {
+            Some(e) => Err(e),
+            None => Ok(self.written),
+        }
+    }
+
+    /// The size of an image of `size` characters, failing when it is too large for the format.
+    fn pixels(&self, (width, height): (usize, usize)) -> Result<(u16, u16)> {
+        let too_large = || io::Error::other(format!("a {width}x{height} frame is too large"));
+        let width = u16::try_from(width * self.scale).map_err(|_| too_large())?;
+        let height = u16::try_from(height * self.scale).map_err(|_| too_large())?;
+        Ok((width, height))
+    }
+
+    fn begin_gif(&mut self, sizes: &[(usize, usize)]) -> Result<()> {
+        let path = if sizes.len() > 1 {
+            let stem = self.path.file_stem().unwrap_or_default().to_string_lossy();
+            self.path
+                .with_file_name(format!("{stem}-part{}.gif", self.part))
+        } else {
+            self.path.clone()
+        };
+        self.gif_size = sizes.get(self.animation - 1).copied().unwrap_or_default();
+        let (width, height) = self.pixels(self.gif_size)?;
+        let file = BufWriter::new(File::create(&path)?);
+        let mut gif = gif::Encoder::new(file, width, height, &self.palette.rgb())
+            .map_err(io::Error::other)?;
+        gif.set_repeat(gif::Repeat::Infinite)
+            .map_err(io::Error::other)?;
+        self.gif = Some(gif);
+        self.written.push(path);
+        Ok(())
+    }
+
+    fn write_gif_frame(&mut self, frame: &str) -> Result<()> {
+        let (width, height) = self.pixels(self.gif_size)?;
+        let pixels = self.palette.rasterize(frame, self.gif_size, self.scale);
+        let frame = gif::Frame {
+            width,
+            height,
+            delay: self.delay,
+            buffer: Cow::Owned(pixels),
+            ..gif::Frame::default()
+        };
+        if let Some(gif) = &mut self.gif {
+            gif.write_frame(&frame).map_err(io::Error::other)?;
+        }
+        Ok(())
+    }
+
+    fn write_png(&mut self, frame: &str) -> Result<()> {
+        let size = frame_size(frame);
+        let (width, height) = self.pixels(size)?;
+        let path = self
+            .path
+            .join(format!("part{}-{:05}.png", self.part, self.frame));
+        let mut png = png::Encoder::new(
+            BufWriter::new(File::create(&path)?),
+            width.into(),
+            height.into(),
+        );
+        png.set_color(png::ColorType::Indexed);
+        png.set_depth(png::BitDepth::Eight);
+        png.set_palette(self.palette.rgb());
+        let mut writer = png.write_header().map_err(io::Error::other)?;
+        writer
+            .write_image_data(&self.palette.rasterize(frame, size, self.scale))
+            .map_err(io::Error::other)?;
+        self.written.push(path);
+        Ok(())
+    }
+}
+
+impl Screen for Export {
+    fn begin(&mut self, animation: &Animation) {
+        self.part = animation.part;
+        self.animation += 1;
+        self.frame = 0;
+        let started = if let Some(sizes) = self.gif_sizes.take() {
+            let started = self.begin_gif(&sizes);
+            self.gif_sizes = Some(sizes);
+            started
+        } else {
+            fs::create_dir_all(&self.path).map_err(Error::from)
+        };
+        if let Err(e) = started {
+            self.error.get_or_insert(e);
+        }
+    }
+
+    fn show(&mut self, frame: &str) -> bool {
+        if self.error.is_some() {
+            return false;
+        }
+        self.frame += 1;
+        if !(self.frame - 1).is_multiple_of(self.every) {
+            return true;
+        }
+        let written = if self.gif_sizes.is_some() {
+            self.write_gif_frame(frame)
+        } else {
+            self.write_png(frame)
+        };
+        match written {
+            Ok(()) => true,
+            Err(e) => {
+                self.error = Some(e);
+                false
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn palette() {
+        let palette: Palette = "#=40c040,x=010203".parse().unwrap();
+        assert_eq!(
+            palette.colors[palette.index('#') as usize].1,
+            [0x40, 0xc0, 0x40]
+        );
+        assert_eq!(palette.colors[palette.index('x') as usize].1, [1, 2, 3]);
+        assert_eq!(palette.index('?') as usize, palette.colors.len());
+        assert_eq!(palette.rgb().len(), 3 * (palette.colors.len() + 1));
+        assert!("#=40c04".parse::<Palette>().is_err());
+        assert!("#40c040".parse::<Palette>().is_err());
+    }
+
+    #[test]
+    fn rasterize() {
+        let palette = Palette::default();
+        let (o, rock, space) = (palette.index('o'), palette.index('#'), palette.background());
+        let frame = "o#\n#\n";
+        assert_eq!(frame_size(frame), (2, 2));
+        let pixels = palette.rasterize(frame, (3, 2), 2);
+        #[rustfmt::skip]
+        assert_eq!(pixels, vec![
+            o, o, rock, rock, space, space,
+            o, o, rock, rock, space, space,
+            rock, rock, space, space, space, space,
+            rock, rock, space, space, space, space,
+        ]);
+    }
+
+    #[test]
+    fn round_trip() {
+        let animation = Animation {
+            name: "test",
+            part: 1,
+            animate: |_, _| Ok(()),
+        };
+        let frames = ["o#\n#\n", "#\n", "oo\n##\n.\n"];
+        let dir = std::env::temp_dir().join(format!("aoc-export-{}", std::process::id()));
+
+        let mut measure = Measure::default();
+        measure.begin(&animation);
+        frames.iter().for_each(|f| assert!(measure.show(f)));
+        assert_eq!(measure.sizes, vec![(2, 3)]);
+
+        let path = dir.join("test.gif");
+        fs::create_dir_all(&dir).unwrap();
+        let mut export =
+            Export::new(&path, Palette::default(), 2, 1, 30).with_gif_sizes(measure.sizes);
+        export.begin(&animation);
+        frames.iter().for_each(|f| assert!(export.show(f)));
+        assert_eq!(export.finish().unwrap(), vec![path.clone()]);
+        let mut options = gif::DecodeOptions::new();
+        options.set_color_output(gif::ColorOutput::Indexed);
+        let mut decoder = options.read_info(File::open(&path).unwrap()).unwrap();
+        assert_eq!((decoder.width(), decoder.height()), (4, 6));
+        let mut count = 0;
+        while let Some(frame) = decoder.read_next_frame().unwrap() {
+            assert_eq!(frame.delay, 3);
+            count += 1;
+        }
+        assert_eq!(count, 3);
+
+        let pngs = dir.join("pngs");
+        let mut export = Export::new(&pngs, Palette::default(), 1, 2, 30);
+        export.begin(&animation);
+        frames.iter().for_each(|f| assert!(export.show(f)));
+        let written = export.finish().unwrap();
+        assert_eq!(
+            written,
+            vec![pngs.join("part1-00001.png"), pngs.join("part1-00003.png")]
+        );
+        let decoder = png::Decoder::new(File::open(&written[1]).unwrap());
+        let reader = decoder.read_info().unwrap();
+        assert_eq!((reader.info().width, reader.info().height), (2, 3));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,142 @@
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use aoc_common::{Error, Result};
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+
+/// The input checked out next to the day's code, `aocXX/input/input.txt`.
+pub fn local_input(day: u8) -> PathBuf {
+    PathBuf::from(format!("aoc{day:02}/input/input.txt"))
+}
+
+/// Where fetched inputs are kept: `$AOC_CACHE_DIR`, or `aoc-2022` in the user's cache directory.
+pub fn cache_dir() -> Option<PathBuf> {
+    if let Some(dir) = env::var_os("AOC_CACHE_DIR") {
+        return Some(dir.into());
+    }
+    let base = env::var_os("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))
+        .or_else(|| env::var_os("LOCALAPPDATA").map(PathBuf::from))?;
+    Some(base.join("aoc-2022"))
+}
+
+pub fn cached_input(cache_dir: &Path, day: u8) -> PathBuf {
+    cache_dir.join(format!("day{day:02}.txt"))
+}
+
+/// The input to run `day` on: the local one when there is one, otherwise the fetched one.
+///
+/// When neither exists the local path is returned, so that the error names the expected file.
+pub fn input_path(day: u8) -> PathBuf {
+    let local = local_input(day);
+    if local.exists() {
+        return local;
+    }
+    match cache_dir().map(|dir| cached_input(&dir, day)) {
+        Some(cached) if cached.exists() => cached,
+        _ => local,
+    }
+}
+
+/// Downloads the input of `day` from `base_url` into `cache_dir`, unless it is already cached.
+///
+/// Returns the path of the cached input and whether it had to be downloaded.
+pub fn fetch(day: u8, base_url: &str, session: &str, cache_dir: &Path) -> Result<(PathBuf, bool)> {
+    let path = cached_input(cache_dir, day);
+    if path.exists() {
+        return Ok((path, false));
+    }
+
+    let url = format!("{}/2022/day/{day}/input", base_url.trim_end_matches('/'));
+    let response = ureq::get(&url)
+        .set("Cookie", &format!("session={session}"))
+        .set(
+            "User-Agent",
+            "github.com/livexia/advent-of-code-2022 aoc fetch",
+        )
+        .call()
+        .map_err(|e| Error::Io(io::Error::other(e.to_string())))?;
+    let input = response.into_string()?;
+
+    // write next to the cache entry first, a failed download must not look cached
+    fs::create_dir_all(cache_dir)?;
+    let partial = path.with_extension("part");
+    fs::write(&partial, input)?;
+    fs::rename(&partial, &path)?;
+    Ok((path, true))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::thread;
+
+    /// Serves every request with the input of a day, like the puzzle site does, and counts the
+    /// requests that carried the session cookie.
+    fn mock_server() -> (String, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(AtomicUsize::new(0));
+        let count = requests.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut request = vec![];
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if line.trim().is_empty() {
+                        break;
+                    }
+                    request.push(line.trim().to_string());
+                }
+                let (status, body) = if request[0] == "GET /2022/day/1/input HTTP/1.1"
+                    && request
+                        .iter()
+                        .any(|h| h.to_lowercase() == "cookie: session=secret")
+                {
+                    count.fetch_add(1, Ordering::SeqCst);
+                    ("200 OK", "1000\n2000\n\n3000\n")
+                } else {
+                    ("400 Bad Request", "Puzzle inputs differ by user.\n")
+                };
+                write!(
+                    stream,
+                    "HTTP/1.1 {status}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                )
+                .unwrap();
+            }
+        });
+        (base_url, requests)
+    }
+
+    #[test]
+    fn fetch_once_then_use_cache() {
+        let (base_url, requests) = mock_server();
+        let cache_dir = env::temp_dir().join(format!("aoc-fetch-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&cache_dir);
+
+        let (path, fetched) = fetch(1, &base_url, "secret", &cache_dir).unwrap();
+        assert!(fetched);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "1000\n2000\n\n3000\n");
+
+        let (cached, fetched) = fetch(1, &base_url, "secret", &cache_dir).unwrap();
+        assert!(!fetched);
+        assert_eq!(cached, path);
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+
+        assert!(fetch(2, &base_url, "secret", &cache_dir).is_err());
+        assert!(!cached_input(&cache_dir, 2).exists());
+        fs::remove_dir_all(&cache_dir).unwrap();
+    }
+}
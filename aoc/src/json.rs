@@ -0,0 +1,125 @@
+use aoc::days::Outcome;
+use aoc_common::{Answer, Error};
+use serde::Serialize;
+use serde_json::Value;
+
+/// One line of `aoc run --format json`.
+///
+/// A day whose input can not be read or parsed gives a single record without a part.
+#[derive(Serialize)]
+pub struct Record {
+    pub day: u8,
+    pub part: Option<u8>,
+    pub answer: Value,
+    pub elapsed_ns: Option<u128>,
+    pub variant: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// The allocations of the part, with the `alloc-stats` feature.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub peak_bytes: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allocations: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allocated_bytes: Option<usize>,
+}
+
+impl Record {
+    pub fn new(day: u8, outcome: &Outcome) -> Self {
+        let (answer, error) = match &outcome.answer {
+            Ok(answer) => (answer_value(answer), None),
+            Err(e) => (Value::Null, Some(e.to_string())),
+        };
+        Record {
+            day,
+            part: Some(outcome.part),
+            answer,
+            elapsed_ns: Some(outcome.elapsed.as_nanos()),
+            variant: outcome.variant,
+            error,
+            peak_bytes: outcome.memory.map(|m| m.peak),
+            allocations: outcome.memory.map(|m| m.allocations),
+            allocated_bytes: outcome.memory.map(|m| m.allocated),
+        }
+    }
+
+    pub fn failed(day: u8, error: &Error) -> Self {
+        Record {
+            day,
+            part: None,
+            answer: Value::Null,
+            elapsed_ns: None,
+            variant: None,
+            error: Some(error.to_string()),
+            peak_bytes: None,
+            allocations: None,
+            allocated_bytes: None,
+        }
+    }
+}
+
+/// Numbers stay numbers so they can be compared, a part without answer is `null`.
+fn answer_value(answer: &Answer) -> Value {
+    match answer {
+        Answer::Number(n) => Value::from(*n),
+        Answer::Text(s) => Value::from(s.as_str()),
+        Answer::None => Value::Null,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use aoc_common::AllocStats;
+
+    use super::*;
+
+    #[test]
+    fn records() {
+        let outcome = Outcome {
+            part: 2,
+            variant: Some("remove and insert"),
+            answer: Ok(Answer::Number(1623178306)),
+            elapsed: Duration::from_micros(3),
+            memory: None,
+        };
+        assert_eq!(
+            serde_json::to_string(&Record::new(20, &outcome)).unwrap(),
+            r#"{"day":20,"part":2,"answer":1623178306,"elapsed_ns":3000,"variant":"remove and insert"}"#
+        );
+
+        let outcome = Outcome {
+            part: 1,
+            variant: None,
+            answer: Ok(Answer::from("CMZ")),
+            elapsed: Duration::from_nanos(42),
+            memory: None,
+        };
+        assert_eq!(
+            serde_json::to_string(&Record::new(5, &outcome)).unwrap(),
+            r#"{"day":5,"part":1,"answer":"CMZ","elapsed_ns":42,"variant":null}"#
+        );
+
+        let outcome = Outcome {
+            memory: Some(AllocStats {
+                peak: 2048,
+                allocations: 3,
+                allocated: 4096,
+            }),
+            ..outcome
+        };
+        assert_eq!(
+            serde_json::to_string(&Record::new(5, &outcome)).unwrap(),
+            r#"{"day":5,"part":1,"answer":"CMZ","elapsed_ns":42,"variant":null,"peak_bytes":2048,"allocations":3,"allocated_bytes":4096}"#
+        );
+
+        let error = Error::parse("no monkey");
+        assert_eq!(
+            serde_json::to_string(&Record::failed(11, &error)).unwrap(),
+            format!(
+                r#"{{"day":11,"part":null,"answer":null,"elapsed_ns":null,"variant":null,"error":"{error}"}}"#
+            )
+        );
+    }
+}
@@ -0,0 +1,8 @@
+//! Every day's solver reachable by its number and the puzzle inputs to run them on, shared by
+//! the runner, the benchmarks and the browser playground.
+//!
+//! Without the default `cli` feature only the solvers are left, which also build for wasm32.
+
+pub mod days;
+#[cfg(feature = "cli")]
+pub mod input;
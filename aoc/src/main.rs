@@ -0,0 +1,480 @@
+use std::fs;
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use aoc::days;
+use aoc::input::{self, input_path, DEFAULT_BASE_URL};
+use aoc_common::{read_input, read_stdin, Result};
+use clap::{ArgGroup, Parser, Subcommand, ValueEnum};
+
+mod export;
+mod json;
+mod scaffold;
+mod verify;
+mod visualize;
+
+#[cfg(feature = "alloc-stats")]
+#[global_allocator]
+static ALLOC: aoc_common::CountingAlloc = aoc_common::CountingAlloc;
+
+#[derive(Parser)]
+#[command(name = "aoc", about = "Run the Advent of Code 2022 solutions")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+    /// Number of threads solving at the same time [default: one per core]
+    #[cfg(feature = "parallel")]
+    #[arg(long, global = true, value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..))]
+    threads: Option<usize>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Run the solver of one day, or of every day when no day is given
+    #[command(group(ArgGroup::new("animation").args(["visualize", "export"])))]
+    Run {
+        /// Day to run, from 1 to 25
+        #[arg(value_parser = clap::value_parser!(u8).range(1..=25))]
+        day: Option<u8>,
+        /// Only run this part of the puzzle
+        #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: Option<u8>,
+        /// Read the puzzle input from this file
+        ///
+        /// Without it, a single day reads its input from stdin when stdin is piped, otherwise
+        /// every day reads `aocXX/input/input.txt`, or the input fetched into the cache.
+        #[arg(short, long, requires = "day")]
+        input: Option<PathBuf>,
+        /// How to print the answers
+        ///
+        /// `json` prints one record per line, for the alternative implementations too.
+        #[arg(short, long, value_enum, default_value_t = Format::Text)]
+        format: Format,
+        /// Play the simulation of the day in the terminal instead of printing the answers
+        ///
+        /// Only some days have one: 5, 14, 17, 22, 23 and 24.
+        #[arg(long, requires = "day", conflicts_with = "format")]
+        visualize: bool,
+        /// Write the frames of the simulation of the day to images instead of printing the answers
+        ///
+        /// A path ending with `.gif` gets an animated GIF, with `-partN` added to the name when
+        /// both parts are exported. Any other path is a directory getting a PNG per frame.
+        #[arg(long, requires = "day", conflicts_with = "format")]
+        export: Option<PathBuf>,
+        /// Pixels per character of the exported frames
+        #[arg(
+            long,
+            default_value_t = 4,
+            requires = "export",
+            value_parser = clap::value_parser!(u32).range(1..=64)
+        )]
+        scale: u32,
+        /// Colors of the characters in the exported frames, like `#=40c040,o=ffffff`
+        ///
+        /// The colors given replace the default ones, characters without a color are magenta.
+        #[arg(long, requires = "export")]
+        palette: Option<export::Palette>,
+        /// Only export one frame out of this many
+        #[arg(
+            long,
+            default_value_t = 1,
+            requires = "export",
+            value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+        )]
+        every: usize,
+        /// Frames per second of the simulation
+        #[arg(
+            long,
+            default_value_t = 30,
+            requires = "animation",
+            value_parser = clap::value_parser!(u32).range(1..=visualize::MAX_FPS as i64)
+        )]
+        fps: u32,
+        /// Start the simulation paused, to step through it frame by frame
+        #[arg(long, requires = "visualize")]
+        paused: bool,
+    },
+    /// Create the crate of a new day and wire it into the workspace, the runner and the benchmarks
+    New {
+        /// Day to create, from 1 to 25
+        #[arg(value_parser = clap::value_parser!(u8).range(1..=25))]
+        day: u8,
+    },
+    /// Download the input of a day into the cache, unless it is already there
+    Fetch {
+        /// Day to fetch, from 1 to 25
+        #[arg(value_parser = clap::value_parser!(u8).range(1..=25))]
+        day: u8,
+        /// Value of the `session` cookie of a logged in browser
+        #[arg(long, env = "AOC_SESSION", hide_env_values = true)]
+        session: Option<String>,
+        /// Site to download from
+        #[arg(long, env = "AOC_BASE_URL", default_value = DEFAULT_BASE_URL)]
+        base_url: String,
+        /// Directory the inputs are cached in [default: the user's cache directory]
+        #[arg(long, env = "AOC_CACHE_DIR")]
+        cache_dir: Option<PathBuf>,
+    },
+    /// Make up a puzzle input of any size, the same one for the same seed
+    Gen {
+        /// Day to make an input for, from 1 to 25
+        #[arg(value_parser = clap::value_parser!(u8).range(1..=25))]
+        day: u8,
+        /// How large the input is, what it counts depends on the day
+        ///
+        /// The default is about the size of the real input.
+        #[arg(long)]
+        size: Option<usize>,
+        /// Seed of the random numbers
+        #[arg(long, default_value_t = 0)]
+        seed: u64,
+        /// Set a knob of the day, like `--knob monkeys=12`, a wrong name lists the knobs
+        #[arg(long = "knob", value_name = "NAME=VALUE", value_parser = parse_knob)]
+        knobs: Vec<(String, String)>,
+        /// Write the input to this file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Run every day and compare the answers with the recorded ones
+    Verify {
+        /// Record the current answers instead of only comparing them
+        #[arg(long)]
+        record: bool,
+        /// File the answers are recorded in
+        #[arg(long, default_value = "answers.toml")]
+        answers: PathBuf,
+    },
+}
+
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+enum Format {
+    Text,
+    Json,
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    if let Err(e) = init_tracing() {
+        eprintln!("AOC_LOG: {e}");
+        return ExitCode::FAILURE;
+    }
+    #[cfg(feature = "parallel")]
+    if let Some(threads) = cli.threads {
+        let pool = rayon::ThreadPoolBuilder::new().num_threads(threads);
+        if let Err(e) = pool.build_global() {
+            eprintln!("{e}");
+            return ExitCode::FAILURE;
+        }
+    }
+    match cli.command {
+        Command::Run {
+            day: Some(day),
+            part,
+            input,
+            export: Some(path),
+            scale,
+            palette,
+            every,
+            fps,
+            ..
+        } => {
+            let export = export::Export::new(&path, palette.unwrap_or_default(), scale, every, fps);
+            export_frames(day, part, input, export)
+        }
+        Command::Run {
+            day: Some(day),
+            part,
+            input,
+            visualize: true,
+            fps,
+            paused,
+            ..
+        } => visualize(day, part, input, fps, paused),
+        Command::Run {
+            day,
+            part,
+            input,
+            format,
+            ..
+        } => run(day, part, input, format),
+        Command::Gen {
+            day,
+            size,
+            seed,
+            knobs,
+            output,
+        } => match generate(day, size, seed, &knobs, output.as_deref()) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => {
+                eprintln!("{e}");
+                ExitCode::FAILURE
+            }
+        },
+        Command::Verify { record, answers } => match verify::verify(&answers, record) {
+            Ok(true) => ExitCode::SUCCESS,
+            Ok(false) => ExitCode::FAILURE,
+            Err(e) => {
+                eprintln!("{e}");
+                ExitCode::FAILURE
+            }
+        },
+        Command::New { day } => match scaffold::new_day(Path::new("."), day) {
+            Ok(()) => {
+                println!("Created aoc{day:02}, fill in the example test and solve it with:");
+                println!("    cargo test -p aoc{day:02} -- --include-ignored");
+                println!("    cargo run -p aoc -- run {day}");
+                ExitCode::SUCCESS
+            }
+            Err(e) => {
+                eprintln!("{e}");
+                ExitCode::FAILURE
+            }
+        },
+        Command::Fetch {
+            day,
+            session,
+            base_url,
+            cache_dir,
+        } => match fetch(day, session, &base_url, cache_dir) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(e) => {
+                eprintln!("{e}");
+                ExitCode::FAILURE
+            }
+        },
+    }
+}
+
+/// Prints the spans and events of the solvers to stderr, filtered by `AOC_LOG` like
+/// `AOC_LOG=aoc16=trace,aoc19=debug`. Without `AOC_LOG` nothing is traced.
+fn init_tracing() -> std::result::Result<(), tracing_subscriber::filter::ParseError> {
+    use tracing_subscriber::fmt::format::FmtSpan;
+    use tracing_subscriber::EnvFilter;
+
+    let Ok(directives) = std::env::var("AOC_LOG") else {
+        return Ok(());
+    };
+    tracing_subscriber::fmt()
+        .with_env_filter(EnvFilter::try_new(directives)?)
+        .with_span_events(FmtSpan::CLOSE)
+        .with_writer(io::stderr)
+        .init();
+    Ok(())
+}
+
+fn fetch(
+    day: u8,
+    session: Option<String>,
+    base_url: &str,
+    cache_dir: Option<PathBuf>,
+) -> Result<()> {
+    let Some(cache_dir) = cache_dir.or_else(input::cache_dir) else {
+        return Err(io::Error::other("can not find a cache directory, set AOC_CACHE_DIR").into());
+    };
+    let cached = input::cached_input(&cache_dir, day);
+    if cached.exists() {
+        println!("Day {day:02} is already cached: {}", cached.display());
+        return Ok(());
+    }
+    let Some(session) = session else {
+        return Err(
+            io::Error::other("no session cookie, pass --session or set AOC_SESSION").into(),
+        );
+    };
+    let (path, _) = input::fetch(day, base_url, &session, &cache_dir)?;
+    println!("Day {day:02} fetched: {}", path.display());
+    Ok(())
+}
+
+fn parse_knob(s: &str) -> std::result::Result<(String, String), String> {
+    match s.split_once('=') {
+        Some((name, value)) => Ok((name.to_string(), value.to_string())),
+        None => Err(format!("{s:?} is not like `name=value`")),
+    }
+}
+
+fn generate(
+    day: u8,
+    size: Option<usize>,
+    seed: u64,
+    knobs: &[(String, String)],
+    output: Option<&Path>,
+) -> Result<()> {
+    let Some(generator) = days::generator(day) else {
+        return Err(io::Error::other(format!("day {day:02} has no generator")).into());
+    };
+    let input = generator.generate(size, seed, knobs).map_err(|e| {
+        let mut message = format!("day {day:02}: {e}\nthe size counts {}", generator.size);
+        for knob in generator.knobs {
+            let (name, about, default) = (knob.name, knob.about, knob.default);
+            message += &format!("\n  --knob {name}=<value>  {about} [default: {default}]");
+        }
+        io::Error::other(message)
+    })?;
+    match output {
+        Some(path) => fs::write(path, input + "\n")?,
+        None => writeln!(io::stdout().lock(), "{input}")?,
+    }
+    Ok(())
+}
+
+/// Reads the input of `day`, see the `--input` option of `aoc run` for where it comes from.
+fn puzzle_input(day: u8, input: Option<&Path>, single_day: bool) -> Result<String> {
+    if let Some(path) = input {
+        return read_input(path);
+    }
+    if single_day && !io::stdin().is_terminal() {
+        let input = read_stdin()?;
+        // nothing piped, like when run from a script without a terminal
+        if !input.is_empty() {
+            return Ok(input);
+        }
+    }
+    read_input(input_path(day))
+}
+
+fn run(day: Option<u8>, part: Option<u8>, input: Option<PathBuf>, format: Format) -> ExitCode {
+    let days: Vec<u8> = match day {
+        Some(day) => vec![day],
+        None => (1..=25).collect(),
+    };
+    let mut failed = 0;
+    let single_day = days.len() == 1;
+    let json = format == Format::Json;
+    let solved = days::map_days(&days, |day| {
+        let input = puzzle_input(day, input.as_deref(), single_day);
+        input.and_then(|input| days::run(day, &input, part, json))
+    });
+    for (day, outcomes) in days.into_iter().zip(solved) {
+        if json {
+            failed += print_json(day, &outcomes);
+            continue;
+        }
+        println!("Day {day:02}");
+        match outcomes {
+            Ok(outcomes) => {
+                for outcome in outcomes {
+                    let n = outcome.part;
+                    match outcome.answer {
+                        Ok(answer) => {
+                            let answer = answer.to_string();
+                            if answer.contains('\n') {
+                                println!("Part{n}: \n{answer}");
+                            } else {
+                                println!("Part{n}: {answer}");
+                            }
+                        }
+                        Err(e) => {
+                            eprintln!("Part{n} failed: {e}");
+                            failed += 1;
+                        }
+                    }
+                    println!("> Time elapsed is: {:?}", outcome.elapsed);
+                    if let Some(memory) = outcome.memory {
+                        println!(
+                            "> Memory: peak {} bytes, {} allocations of {} bytes",
+                            memory.peak, memory.allocations, memory.allocated
+                        );
+                    }
+                }
+            }
+            Err(e) => {
+                eprintln!("Day {day:02} failed: {e}");
+                failed += 1;
+            }
+        }
+    }
+    if failed > 0 {
+        eprintln!("{failed} failure(s)");
+        return ExitCode::FAILURE;
+    }
+    ExitCode::SUCCESS
+}
+
+fn visualize(
+    day: u8,
+    part: Option<u8>,
+    input: Option<PathBuf>,
+    fps: u32,
+    paused: bool,
+) -> ExitCode {
+    let played = puzzle_input(day, input.as_deref(), true).and_then(|input| {
+        if !io::stdout().is_terminal() {
+            return Err(io::Error::other("--visualize needs a terminal to draw on").into());
+        }
+        let mut terminal = visualize::Terminal::new(fps, paused)?;
+        let played = days::animate(day, &input, part, &mut terminal)?;
+        if played > 0 {
+            terminal.finish()?;
+        }
+        Ok(played)
+    });
+    match played {
+        Ok(0) => {
+            eprintln!("Day {day:02} has nothing to visualize");
+            ExitCode::FAILURE
+        }
+        Ok(_) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Day {day:02} failed: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn export_frames(
+    day: u8,
+    part: Option<u8>,
+    input: Option<PathBuf>,
+    export: export::Export,
+) -> ExitCode {
+    let written = puzzle_input(day, input.as_deref(), true).and_then(|input| {
+        let mut export = if export.is_gif() {
+            // a GIF needs the size of the largest frame before the first one is written
+            let mut measure = export::Measure::default();
+            days::animate(day, &input, part, &mut measure)?;
+            export.with_gif_sizes(measure.sizes)
+        } else {
+            export
+        };
+        days::animate(day, &input, part, &mut export)?;
+        export.finish()
+    });
+    match written {
+        Ok(written) if written.is_empty() => {
+            eprintln!("Day {day:02} has nothing to export");
+            ExitCode::FAILURE
+        }
+        Ok(written) => {
+            if written.len() > 3 {
+                let dir = written[0].parent().unwrap_or(Path::new("."));
+                println!("Wrote {} frames to {}", written.len(), dir.display());
+            } else {
+                for path in written {
+                    println!("Wrote {}", path.display());
+                }
+            }
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("Day {day:02} failed: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Prints the records of `day`, returns how many of them are failures.
+fn print_json(day: u8, outcomes: &Result<Vec<days::Outcome>>) -> usize {
+    let records = match outcomes {
+        Ok(outcomes) => outcomes.iter().map(|o| json::Record::new(day, o)).collect(),
+        Err(e) => vec![json::Record::failed(day, e)],
+    };
+    for record in &records {
+        println!(
+            "{}",
+            serde_json::to_string(record).expect("a record is valid json")
+        );
+    }
+    records.iter().filter(|r| r.error.is_some()).count()
+}
@@ -0,0 +1,259 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use aoc_common::{Error, Result};
+
+/// Creates the crate of `day` under `root`, the workspace root, and wires it into the workspace,
+/// the runner and the benchmarks.
+///
+/// Nothing is written when the day already exists in any of them.
+pub fn new_day(root: &Path, day: u8) -> Result<()> {
+    let name = format!("aoc{day:02}");
+    let dir = root.join(&name);
+    if dir.exists() {
+        return Err(exists(format!("{} already exists", dir.display())));
+    }
+
+    // prepare every edit first, so that a refused one leaves the tree untouched
+    let workspace = root.join("Cargo.toml");
+    let runner = root.join("aoc/Cargo.toml");
+    let registry = root.join("aoc/src/days.rs");
+    let edits = [
+        (
+            &workspace,
+            insert_day_line(
+                &fs::read_to_string(&workspace)?,
+                day,
+                &format!("    \"{name}\","),
+                |l| l.starts_with("members = ["),
+                |l| l == "]",
+            )?,
+        ),
+        (
+            &runner,
+            insert_day_line(
+                &fs::read_to_string(&runner)?,
+                day,
+                &format!("{name} = {{ path = \"../{name}\" }}"),
+                |l| l == "[dependencies]",
+                |l| l.is_empty() || l.starts_with('['),
+            )?,
+        ),
+        (
+            &registry,
+            insert_day_line(
+                &fs::read_to_string(&registry)?,
+                day,
+                &format!("        {day} => visitor.visit::<{name}::Day{day:02}>(day),"),
+                |l| l.trim_start().starts_with("match day"),
+                |l| l.trim_start().starts_with("_ =>"),
+            )?,
+        ),
+    ];
+
+    fs::create_dir_all(dir.join("src"))?;
+    fs::write(dir.join("Cargo.toml"), cargo_toml(&name))?;
+    fs::write(dir.join("src/lib.rs"), lib_rs(day))?;
+    fs::write(dir.join("src/main.rs"), main_rs(day))?;
+    for (path, content) in edits {
+        fs::write(path, content)?;
+    }
+    Ok(())
+}
+
+fn exists(message: String) -> Error {
+    Error::Io(io::Error::new(io::ErrorKind::AlreadyExists, message))
+}
+
+/// The day a line is about, from the first `aocXX` in it.
+fn day_of(line: &str) -> Option<u8> {
+    let (_, rest) = line.split_once("aoc")?;
+    rest.get(..2)?.parse().ok()
+}
+
+/// Inserts `line` among the lines of the other days, keeping them ordered. The days are only looked
+/// for in the section after the first line matching `start`, up to the first one matching `end`;
+/// without any other day yet, `line` goes at the end of the section.
+fn insert_day_line(
+    text: &str,
+    day: u8,
+    line: &str,
+    start: impl Fn(&str) -> bool,
+    end: impl Fn(&str) -> bool,
+) -> Result<String> {
+    let mut lines: Vec<&str> = text.lines().collect();
+    let first = lines.iter().position(|l| start(l)).map_or(0, |i| i + 1);
+    let last = lines[first..]
+        .iter()
+        .position(|l| end(l))
+        .map_or(lines.len(), |i| first + i);
+    let days: Vec<(usize, u8)> = (first..last)
+        .filter_map(|i| Some((i, day_of(lines[i])?)))
+        .collect();
+    if days.iter().any(|&(_, d)| d == day) {
+        return Err(exists(format!("day {day} is already wired: {line}")));
+    }
+    let index = match (days.first(), days.iter().rfind(|&&(_, d)| d < day)) {
+        (_, Some(&(before, _))) => before + 1,
+        (Some(&(first, _)), None) => first,
+        (None, None) => last,
+    };
+    lines.insert(index, line);
+    let mut text = lines.join("\n");
+    text.push('\n');
+    Ok(text)
+}
+
+fn cargo_toml(name: &str) -> String {
+    format!(
+        r#"[package]
+name = "{name}"
+version = "0.1.0"
+edition = "2021"
+
+# See more keys and their definitions at https://doc.rust-lang.org/cargo/reference/manifest.html
+
+[dependencies]
+aoc-common = {{ path = "../aoc-common" }}
+"#
+    )
+}
+
+fn lib_rs(day: u8) -> String {
+    format!(
+        r#"use aoc_common::{{Answer, Result, Solution}};
+
+pub struct Day{day:02};
+
+impl Solution for Day{day:02} {{
+    type Input = Vec<String>;
+
+    fn parse(input: &str) -> Result<Self::Input> {{
+        Ok(input.lines().map(|l| l.trim().to_string()).collect())
+    }}
+
+    fn part1(_input: &Self::Input) -> Result<Answer> {{
+        todo!()
+    }}
+
+    fn part2(_input: &Self::Input) -> Result<Answer> {{
+        todo!()
+    }}
+}}
+
+#[test]
+#[ignore = "fill in the example of the puzzle"]
+fn example_input() {{
+    let input = "";
+    let input = Day{day:02}::parse(input).unwrap();
+    assert_eq!(Day{day:02}::part1(&input).unwrap().to_string(), "");
+    assert_eq!(Day{day:02}::part2(&input).unwrap().to_string(), "");
+}}
+"#
+    )
+}
+
+fn main_rs(day: u8) -> String {
+    format!(
+        r#"use std::io::{{self, Write}};
+use std::time::Instant;
+
+use aoc{day:02}::Day{day:02};
+use aoc_common::{{read_stdin, Result, Solution}};
+
+fn main() -> Result<()> {{
+    let input = Day{day:02}::parse(&read_stdin()?)?;
+
+    let start = Instant::now();
+    let result = Day{day:02}::part1(&input)?;
+    writeln!(io::stdout(), "Part1: {{result}}")?;
+    writeln!(io::stdout(), "> Time elapsed is: {{:?}}", start.elapsed())?;
+
+    let start = Instant::now();
+    let result = Day{day:02}::part2(&input)?;
+    writeln!(io::stdout(), "Part2: {{result}}")?;
+    writeln!(io::stdout(), "> Time elapsed is: {{:?}}", start.elapsed())?;
+    Ok(())
+}}
+"#
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    #[test]
+    fn wire_new_day() {
+        let root = env::temp_dir().join(format!("aoc-new-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("aoc/src")).unwrap();
+        fs::write(
+            root.join("Cargo.toml"),
+            "[workspace]\nmembers = [\n    \"aoc\",\n    \"aoc01\",\n    \"aoc03\",\n]\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join("aoc/Cargo.toml"),
+            "[package]\nname = \"aoc\"\n\n[dependencies]\nclap = \"4\"\n\n[dev-dependencies]\n",
+        )
+        .unwrap();
+        let registry =
+            "    match day {\n        1 => visitor.visit::<aoc01::Day01>(day),\n        \
+                        _ => panic!(),\n    }\n";
+        fs::write(root.join("aoc/src/days.rs"), registry).unwrap();
+
+        new_day(&root, 2).unwrap();
+        new_day(&root, 4).unwrap();
+        let workspace = fs::read_to_string(root.join("Cargo.toml")).unwrap();
+        assert!(workspace.contains("\"aoc01\",\n    \"aoc02\",\n    \"aoc03\",\n    \"aoc04\",\n]"));
+        let runner = fs::read_to_string(root.join("aoc/Cargo.toml")).unwrap();
+        assert!(runner.contains(
+            "clap = \"4\"\naoc02 = { path = \"../aoc02\" }\naoc04 = { path = \"../aoc04\" }\n\n"
+        ));
+        let registry = fs::read_to_string(root.join("aoc/src/days.rs")).unwrap();
+        assert!(registry.contains("(day),\n        2 => visitor.visit::<aoc02::Day02>(day),\n"));
+        assert!(registry.contains("        4 => visitor.visit::<aoc04::Day04>(day),\n        _ =>"));
+        assert!(root.join("aoc04/src/lib.rs").exists());
+
+        // refuse to overwrite a day, whether its crate or only its wiring exists
+        fs::write(root.join("aoc04/src/lib.rs"), "edited").unwrap();
+        assert!(new_day(&root, 4).is_err());
+        assert!(new_day(&root, 1).is_err());
+        assert!(!root.join("aoc01").exists());
+        assert_eq!(
+            fs::read_to_string(root.join("aoc04/src/lib.rs")).unwrap(),
+            "edited"
+        );
+        assert_eq!(
+            fs::read_to_string(root.join("Cargo.toml")).unwrap(),
+            workspace
+        );
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn wire_beside_features() {
+        let runner = "[dependencies]\nclap = \"4\"\naoc01 = { path = \"../aoc01\" }\n\n\
+                      [features]\nparallel = [\"aoc01/parallel\", \"aoc05/parallel\"]\n";
+        let dependency = |day: u8| format!("aoc{day:02} = {{ path = \"../aoc{day:02}\" }}");
+        let wire = |text: &str, day| {
+            insert_day_line(
+                text,
+                day,
+                &dependency(day),
+                |l| l == "[dependencies]",
+                |l| l.is_empty() || l.starts_with('['),
+            )
+        };
+        let runner = wire(runner, 5).unwrap();
+        let runner = wire(&runner, 6).unwrap();
+        assert!(runner.contains(
+            "aoc01 = { path = \"../aoc01\" }\naoc05 = { path = \"../aoc05\" }\n\
+             aoc06 = { path = \"../aoc06\" }\n\n[features]"
+        ));
+        assert!(wire(&runner, 5).is_err());
+    }
+}
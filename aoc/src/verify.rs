@@ -0,0 +1,265 @@
+use std::fs;
+use std::path::Path;
+
+use aoc::days;
+use aoc::input::input_path;
+use aoc_common::{input_hash, read_input, Answer, Error, Result};
+use serde::{Deserialize, Serialize};
+
+/// Answers recorded in `answers.toml`, keyed by day, part and the hash of the input they were
+/// computed from.
+#[derive(Default, Serialize, Deserialize)]
+pub struct Answers {
+    #[serde(default, rename = "answer")]
+    answers: Vec<Recorded>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Recorded {
+    day: u8,
+    part: u8,
+    input: String,
+    answer: String,
+}
+
+impl Answers {
+    /// Loads the recorded answers, a missing file has no answers yet.
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Answers::default());
+        }
+        let answers = read_input(path)?;
+        toml::from_str(&answers)
+            .map_err(|e| Error::parse(format!("{}: {}", path.display(), e.message())))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let answers = toml::to_string(self).map_err(|e| Error::Invariant(e.to_string()))?;
+        fs::write(path, answers)?;
+        Ok(())
+    }
+
+    fn get(&self, day: u8, part: u8, input: &str) -> Option<&str> {
+        self.answers
+            .iter()
+            .find(|r| r.day == day && r.part == part && r.input == input)
+            .map(|r| r.answer.as_str())
+    }
+
+    fn record(&mut self, day: u8, part: u8, input: &str, answer: String) {
+        match self
+            .answers
+            .iter_mut()
+            .find(|r| r.day == day && r.part == part && r.input == input)
+        {
+            Some(recorded) => recorded.answer = answer,
+            None => self.answers.push(Recorded {
+                day,
+                part,
+                input: input.to_string(),
+                answer,
+            }),
+        }
+        self.answers.sort_by_key(|r| (r.day, r.part));
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Status {
+    /// The answer matches the recorded one.
+    Pass,
+    /// The answer differs from the recorded one.
+    Regression,
+    /// The solver returned an error.
+    Fail,
+    /// There is no recorded answer for this input yet.
+    Unrecorded,
+    /// There is no input to run the solver on.
+    Missing,
+}
+
+impl Status {
+    fn name(self) -> &'static str {
+        match self {
+            Status::Pass => "pass",
+            Status::Regression => "REGRESSION",
+            Status::Fail => "FAIL",
+            Status::Unrecorded => "unrecorded",
+            Status::Missing => "no input",
+        }
+    }
+}
+
+struct Row {
+    day: u8,
+    part: u8,
+    status: Status,
+    answer: String,
+    expected: String,
+    time: String,
+}
+
+/// Runs every day on its input and compares the answers with the recorded ones, printing one
+/// row per part. With `record`, the current answers are written back to `answers_path`.
+///
+/// Returns whether no part failed or regressed.
+pub fn verify(answers_path: &Path, record: bool) -> Result<bool> {
+    let mut answers = Answers::load(answers_path)?;
+    let mut rows = vec![];
+    let days: Vec<u8> = (1..=25).collect();
+    let solved = days::map_days(&days, |day| {
+        let input = read_input(input_path(day)).ok()?;
+        Some((input_hash(&input), days::run(day, &input, None, false)))
+    });
+    for (day, solved) in days.into_iter().zip(solved) {
+        let (hash, outcomes) = match solved {
+            Some(solved) => solved,
+            None => {
+                rows.extend(parts(day).map(|part| Row {
+                    day,
+                    part,
+                    status: Status::Missing,
+                    answer: String::new(),
+                    expected: String::new(),
+                    time: String::new(),
+                }));
+                continue;
+            }
+        };
+        let outcomes = match outcomes {
+            Ok(outcomes) => outcomes,
+            Err(e) => {
+                rows.extend(parts(day).map(|part| Row {
+                    day,
+                    part,
+                    status: Status::Fail,
+                    answer: e.to_string(),
+                    expected: answers.get(day, part, &hash).map(short).unwrap_or_default(),
+                    time: String::new(),
+                }));
+                continue;
+            }
+        };
+        for outcome in outcomes {
+            let part = outcome.part;
+            let expected = answers.get(day, part, &hash).map(str::to_string);
+            let (status, answer) = match outcome.answer {
+                Ok(Answer::None) => continue,
+                Ok(answer) => {
+                    let answer = answer.to_string();
+                    let status = match &expected {
+                        Some(expected) if expected == &answer => Status::Pass,
+                        Some(_) => Status::Regression,
+                        None => Status::Unrecorded,
+                    };
+                    if record {
+                        answers.record(day, part, &hash, answer.clone());
+                    }
+                    (status, answer)
+                }
+                Err(e) => (Status::Fail, e.to_string()),
+            };
+            rows.push(Row {
+                day,
+                part,
+                status,
+                answer: short(&answer),
+                expected: expected.as_deref().map(short).unwrap_or_default(),
+                time: format!("{:?}", outcome.elapsed),
+            });
+        }
+    }
+    print_table(&rows);
+    if record {
+        answers.save(answers_path)?;
+        println!("recorded answers to {}", answers_path.display());
+    }
+    Ok(rows
+        .iter()
+        .all(|r| !matches!(r.status, Status::Fail | Status::Regression)))
+}
+
+fn parts(day: u8) -> impl Iterator<Item = u8> {
+    if day == 25 {
+        1..=1
+    } else {
+        1..=2
+    }
+}
+
+/// Keeps multi-line answers, like the letters drawn on day 10, on a single table row.
+fn short(answer: &str) -> String {
+    if answer.contains('\n') {
+        format!("<{} lines>", answer.lines().count())
+    } else {
+        answer.to_string()
+    }
+}
+
+fn print_table(rows: &[Row]) {
+    let header = ["Day", "Part", "Status", "Answer", "Expected", "Time"];
+    let cells: Vec<[String; 6]> = rows
+        .iter()
+        .map(|r| {
+            [
+                format!("{:02}", r.day),
+                r.part.to_string(),
+                r.status.name().to_string(),
+                r.answer.clone(),
+                r.expected.clone(),
+                r.time.clone(),
+            ]
+        })
+        .collect();
+    let mut widths = header.map(str::len);
+    for row in &cells {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    let line = |row: &[&str]| {
+        row.iter()
+            .zip(widths)
+            .map(|(cell, width)| format!("{cell:<width$}"))
+            .collect::<Vec<_>>()
+            .join(" | ")
+            .trim_end()
+            .to_string()
+    };
+    println!("{}", line(&header));
+    println!("{}", widths.map(|w| "-".repeat(w)).join("-|-"));
+    for row in &cells {
+        println!("{}", line(&row.each_ref().map(String::as_str)));
+    }
+
+    let count = |status| rows.iter().filter(|r| r.status == status).count();
+    println!(
+        "\n{} passed, {} failed, {} regressed, {} unrecorded, {} without input",
+        count(Status::Pass),
+        count(Status::Fail),
+        count(Status::Regression),
+        count(Status::Unrecorded),
+        count(Status::Missing),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn record_answers() {
+        let mut answers = Answers::default();
+        answers.record(2, 1, "hash", "15".to_string());
+        answers.record(1, 2, "hash", "45000".to_string());
+        answers.record(2, 1, "hash", "16".to_string());
+        answers.record(2, 1, "other", "17".to_string());
+
+        let answers: Answers = toml::from_str(&toml::to_string(&answers).unwrap()).unwrap();
+        assert_eq!(answers.answers.len(), 3);
+        assert_eq!(answers.answers[0].day, 1);
+        assert_eq!(answers.get(2, 1, "hash"), Some("16"));
+        assert_eq!(answers.get(2, 1, "other"), Some("17"));
+        assert_eq!(answers.get(2, 2, "hash"), None);
+    }
+}
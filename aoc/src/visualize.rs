@@ -0,0 +1,150 @@
+use std::io::{self, Stdout, Write};
+use std::time::{Duration, Instant};
+
+use aoc_common::{Animation, Screen};
+use crossterm::cursor::{Hide, MoveTo, Show};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::style::Print;
+use crossterm::terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::{execute, queue};
+
+pub const MAX_FPS: u32 = 1000;
+
+const HELP: &str = "space: pause, n: step, +/-: speed, q: quit";
+
+/// Plays the animations of `aoc run --visualize`, the terminal is restored when it is dropped.
+///
+/// Frames larger than the terminal are cut on the right and at the bottom.
+pub struct Terminal {
+    out: Stdout,
+    fps: u32,
+    paused: bool,
+    quit: bool,
+    title: String,
+    frame: usize,
+}
+
+impl Terminal {
+    pub fn new(fps: u32, paused: bool) -> io::Result<Self> {
+        let mut out = io::stdout();
+        terminal::enable_raw_mode()?;
+        execute!(out, EnterAlternateScreen, Hide)?;
+        Ok(Terminal {
+            out,
+            fps,
+            paused,
+            quit: false,
+            title: String::new(),
+            frame: 0,
+        })
+    }
+
+    /// Keeps the last frame on screen until a key is pressed, unless the viewer already quit.
+    pub fn finish(&mut self) -> io::Result<()> {
+        if self.quit {
+            return Ok(());
+        }
+        self.title.push_str(", done");
+        self.paused = true;
+        self.draw_status()?;
+        loop {
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press {
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    fn draw(&mut self, frame: &str) -> io::Result<()> {
+        let (columns, rows) = terminal::size()?;
+        // the last row is the status line
+        let mut y = 0;
+        for line in frame.lines().take(rows.saturating_sub(1).into()) {
+            let line: String = line.chars().take(columns.into()).collect();
+            queue!(
+                self.out,
+                MoveTo(0, y),
+                Print(line),
+                Clear(ClearType::UntilNewLine)
+            )?;
+            y += 1;
+        }
+        queue!(self.out, MoveTo(0, y), Clear(ClearType::FromCursorDown))?;
+        self.draw_status()
+    }
+
+    fn draw_status(&mut self) -> io::Result<()> {
+        let (columns, rows) = terminal::size()?;
+        let state = if self.paused { "paused" } else { "playing" };
+        let status = format!(
+            "{}, frame {}, {} fps, {state} ({HELP})",
+            self.title, self.frame, self.fps
+        );
+        let status: String = status.chars().take(columns.into()).collect();
+        queue!(
+            self.out,
+            MoveTo(0, rows.saturating_sub(1)),
+            Print(status),
+            Clear(ClearType::UntilNewLine)
+        )?;
+        self.out.flush()
+    }
+
+    /// Handles the keys until the next frame is due, returns `false` when the viewer quits.
+    fn wait(&mut self) -> io::Result<bool> {
+        let due = Instant::now() + Duration::from_secs(1) / self.fps;
+        loop {
+            if !self.paused && !event::poll(due.saturating_duration_since(Instant::now()))? {
+                return Ok(true);
+            }
+            let Event::Key(KeyEvent {
+                code,
+                modifiers,
+                kind: KeyEventKind::Press,
+                ..
+            }) = event::read()?
+            else {
+                continue;
+            };
+            match code {
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(false),
+                // raw mode turns ctrl-c into a key
+                KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => {
+                    return Ok(false)
+                }
+                KeyCode::Char('n') | KeyCode::Right if self.paused => return Ok(true),
+                KeyCode::Char(' ') => self.paused = !self.paused,
+                KeyCode::Char('+') => self.fps = (self.fps * 2).min(MAX_FPS),
+                KeyCode::Char('-') => self.fps = (self.fps / 2).max(1),
+                _ => continue,
+            }
+            self.draw_status()?;
+        }
+    }
+}
+
+impl Screen for Terminal {
+    fn begin(&mut self, animation: &Animation) {
+        self.title = format!("Part{}: {}", animation.part, animation.name);
+        self.frame = 0;
+    }
+
+    fn show(&mut self, frame: &str) -> bool {
+        if self.quit {
+            return false;
+        }
+        self.frame += 1;
+        // a terminal that can not be drawn on any more can not be watched either
+        let go_on = self.draw(frame).and_then(|()| self.wait()).unwrap_or(false);
+        self.quit = !go_on;
+        go_on
+    }
+}
+
+impl Drop for Terminal {
+    fn drop(&mut self) {
+        let _ = execute!(self.out, Show, LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
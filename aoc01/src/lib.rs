@@ -0,0 +1,350 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::io::BufRead;
+
+use aoc_common::{
+    parse_err, unsolvable, Answer, Generator, Knob, Knobs, Position, Result, Rng, Solution,
+};
+
+pub struct Day01;
+
+impl Solution for Day01 {
+    type Input = Elves;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_input(input)
+    }
+
+    fn part1(elves: &Self::Input) -> Result<Answer> {
+        part1(elves).map(Answer::from)
+    }
+
+    fn part2(elves: &Self::Input) -> Result<Answer> {
+        part2(elves).map(Answer::from)
+    }
+
+    fn generator() -> Option<Generator> {
+        Some(Generator {
+            size: "elves",
+            default_size: 250,
+            knobs: &[Knob {
+                name: "items",
+                about: "most food items an elf carries",
+                default: "15",
+            }],
+            generate,
+        })
+    }
+}
+
+/// What is left of the elves once they are read: the `k` largest totals and a few stats.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Elves {
+    /// The largest totals, largest first, at most `k` of them.
+    pub top: Vec<i64>,
+    pub count: u64,
+    /// NaN without any elf, like the median.
+    pub mean: f64,
+    /// Estimated once there are more than 5 elves, see [`Median`].
+    pub median: f64,
+    /// How many elves carry as much as the last of `top` but were left out of it.
+    pub ties: u64,
+}
+
+/// Totals the elves one line at a time, in memory bounded by `k` whatever the input size.
+#[derive(Debug, Clone)]
+pub struct Calories {
+    k: usize,
+    top: BinaryHeap<Reverse<i64>>,
+    ties: u64,
+    count: u64,
+    sum: i128,
+    median: Median,
+    /// The total of the elf being read.
+    current: i64,
+    /// Whether the elf being read has an item yet, blank lines alone do not make an elf.
+    reading: bool,
+    line: usize,
+}
+
+impl Calories {
+    pub fn new(k: usize) -> Self {
+        Calories {
+            k,
+            top: BinaryHeap::with_capacity(k + 1),
+            ties: 0,
+            count: 0,
+            sum: 0,
+            median: Median::default(),
+            current: 0,
+            reading: false,
+            line: 0,
+        }
+    }
+
+    /// Adds a line of the input, without its line ending, a blank line starts a new elf.
+    pub fn push_line(&mut self, line: &str) -> Result<()> {
+        self.line += 1;
+        if line.is_empty() {
+            if self.reading {
+                self.end_elf();
+            }
+            return Ok(());
+        }
+        let calories = line.parse::<i64>().at_line(self.line)?;
+        self.current = match self.current.checked_add(calories) {
+            Some(total) => total,
+            None => return parse_err!("too many calories").at_line(self.line),
+        };
+        self.reading = true;
+        Ok(())
+    }
+
+    fn end_elf(&mut self) {
+        let total = std::mem::take(&mut self.current);
+        self.reading = false;
+        self.count += 1;
+        self.sum += i128::from(total);
+        self.median.push(total as f64);
+        if self.k == 0 {
+            return;
+        }
+        if self.top.len() < self.k {
+            self.top.push(Reverse(total));
+            return;
+        }
+        let Reverse(least) = *self.top.peek().expect("the heap holds k > 0 totals");
+        if total < least {
+            return;
+        }
+        if total == least {
+            self.ties += 1;
+            return;
+        }
+        self.top.pop();
+        self.top.push(Reverse(total));
+        // the tied elves left out only count while the least total stays the same
+        if self.top.peek() == Some(&Reverse(least)) {
+            self.ties += 1;
+        } else {
+            self.ties = 0;
+        }
+    }
+
+    /// Ends the last elf, if the input did not end with a blank line already.
+    pub fn finish(mut self) -> Elves {
+        if self.reading {
+            self.end_elf();
+        }
+        let mut top: Vec<i64> = self.top.into_iter().map(|Reverse(total)| total).collect();
+        top.sort_unstable_by(|a, b| b.cmp(a));
+        Elves {
+            top,
+            count: self.count,
+            mean: self.sum as f64 / self.count as f64,
+            median: self.median.get(),
+            ties: self.ties,
+        }
+    }
+}
+
+/// Reads the elves from `reader` until its end, keeping the `k` largest totals.
+pub fn read_calories(mut reader: impl BufRead, k: usize) -> Result<Elves> {
+    let mut calories = Calories::new(k);
+    let mut line = String::new();
+    while reader.read_line(&mut line)? > 0 {
+        let trimmed = line.strip_suffix('\n').unwrap_or(&line);
+        calories.push_line(trimmed.strip_suffix('\r').unwrap_or(trimmed))?;
+        line.clear();
+    }
+    Ok(calories.finish())
+}
+
+/// The median of a stream in constant memory, with the P² algorithm of Jain and Chlamtac.
+///
+/// Exact up to 5 values, then the middle of five markers moved along the stream as the
+/// heights of a piecewise parabola.
+#[derive(Debug, Clone, Default)]
+pub struct Median {
+    heights: [f64; 5],
+    positions: [f64; 5],
+    desired: [f64; 5],
+    count: usize,
+}
+
+impl Median {
+    const INCREMENTS: [f64; 5] = [0.0, 0.25, 0.5, 0.75, 1.0];
+
+    pub fn push(&mut self, x: f64) {
+        if self.count < 5 {
+            self.heights[self.count] = x;
+            self.count += 1;
+            self.heights[..self.count].sort_by(f64::total_cmp);
+            if self.count == 5 {
+                self.positions = [0.0, 1.0, 2.0, 3.0, 4.0];
+                self.desired = [0.0, 1.0, 2.0, 3.0, 4.0];
+            }
+            return;
+        }
+        self.count += 1;
+        let q = &mut self.heights;
+        let cell = if x < q[0] {
+            q[0] = x;
+            0
+        } else if x >= q[4] {
+            q[4] = x;
+            3
+        } else {
+            (0..4).rfind(|&i| q[i] <= x).unwrap_or(0)
+        };
+        for n in &mut self.positions[cell + 1..] {
+            *n += 1.0;
+        }
+        for (n, dn) in self.desired.iter_mut().zip(Self::INCREMENTS) {
+            *n += dn;
+        }
+        for i in 1..4 {
+            let n = &mut self.positions;
+            let d = self.desired[i] - n[i];
+            if (d >= 1.0 && n[i + 1] - n[i] > 1.0) || (d <= -1.0 && n[i - 1] - n[i] < -1.0) {
+                let d = d.signum();
+                let parabolic = q[i]
+                    + d / (n[i + 1] - n[i - 1])
+                        * ((n[i] - n[i - 1] + d) * (q[i + 1] - q[i]) / (n[i + 1] - n[i])
+                            + (n[i + 1] - n[i] - d) * (q[i] - q[i - 1]) / (n[i] - n[i - 1]));
+                q[i] = if q[i - 1] < parabolic && parabolic < q[i + 1] {
+                    parabolic
+                } else {
+                    let j = if d > 0.0 { i + 1 } else { i - 1 };
+                    q[i] + d * (q[j] - q[i]) / (n[j] - n[i])
+                };
+                n[i] += d;
+            }
+        }
+    }
+
+    /// NaN before the first value.
+    pub fn get(&self) -> f64 {
+        match self.count {
+            0 => f64::NAN,
+            1..=4 if self.count.is_multiple_of(2) => {
+                (self.heights[self.count / 2 - 1] + self.heights[self.count / 2]) / 2.0
+            }
+            1..=4 => self.heights[self.count / 2],
+            _ => self.heights[2],
+        }
+    }
+}
+
+pub fn parse_input(input: &str) -> Result<Elves> {
+    read_calories(input.as_bytes(), 3)
+}
+
+/// The calories carried by the `n` elves carrying the most.
+pub fn top(elves: &Elves, n: usize) -> Result<i64> {
+    if elves.count < n as u64 {
+        return unsolvable!("there are only {} elves", elves.count);
+    }
+    if elves.top.len() < n {
+        return unsolvable!("only the top {} elves were kept", elves.top.len());
+    }
+    match elves.top[..n]
+        .iter()
+        .try_fold(0i64, |sum, &c| sum.checked_add(c))
+    {
+        Some(sum) => Ok(sum),
+        None => unsolvable!("the top {n} elves carry too many calories"),
+    }
+}
+
+pub fn part1(elves: &Elves) -> Result<i64> {
+    top(elves, 1)
+}
+
+pub fn part2(elves: &Elves) -> Result<i64> {
+    top(elves, 3)
+}
+
+fn generate(rng: &mut Rng, elves: usize, knobs: &Knobs) -> Result<String> {
+    let items: i64 = knobs.get("items")?;
+    let elves: Vec<String> = (0..elves.max(3))
+        .map(|_| {
+            let calories: Vec<_> = (0..rng.between(1, items.max(1)))
+                .map(|_| rng.between(1000, 60000).to_string())
+                .collect();
+            calories.join("\n")
+        })
+        .collect();
+    Ok(elves.join("\n\n"))
+}
+
+#[test]
+fn example_input() {
+    let input = "1000
+2000
+3000
+
+4000
+
+5000
+6000
+
+7000
+8000
+9000
+
+10000";
+    let elves = parse_input(input).unwrap();
+    assert_eq!(part1(&elves).unwrap(), 24000);
+    assert_eq!(part2(&elves).unwrap(), 45000);
+    assert_eq!(elves.count, 5);
+    assert_eq!(elves.mean, 11000.0);
+    assert_eq!(elves.median, 10000.0);
+    assert_eq!(elves.ties, 0);
+}
+
+#[test]
+fn top_k_with_ties() {
+    let input = "5\n\n7\n\n5\n\n3\n\n5\r\n\r\n4000000000\n4000000000\n";
+    let elves = read_calories(input.as_bytes(), 2).unwrap();
+    assert_eq!(elves.top, [8000000000, 7]);
+    assert_eq!(elves.count, 6);
+    assert_eq!(elves.ties, 0);
+    let elves = read_calories(input.as_bytes(), 3).unwrap();
+    assert_eq!(elves.top, [8000000000, 7, 5]);
+    assert_eq!(elves.ties, 2);
+    assert_eq!(elves.median, 5.0);
+    assert_eq!(top(&elves, 3).unwrap(), 8000000012);
+    assert!(top(&elves, 4).is_err());
+    assert_eq!(read_calories(input.as_bytes(), 10).unwrap().top.len(), 6);
+    assert!(read_calories("1\n\nx".as_bytes(), 3).is_err());
+}
+
+#[test]
+fn blank_lines() {
+    let elves = parse_input("100\n\n200\n\n300\n\n").unwrap();
+    assert_eq!(elves.count, 3);
+    assert_eq!(elves.mean, 200.0);
+    assert_eq!(elves.median, 200.0);
+    assert_eq!(part2(&elves).unwrap(), 600);
+    let elves = parse_input("100\n\n\n200\n").unwrap();
+    assert_eq!(elves.top, [200, 100]);
+    assert!(part2(&elves).is_err());
+    let elves = parse_input("").unwrap();
+    assert_eq!(elves.count, 0);
+    assert!(part1(&elves).is_err());
+}
+
+#[test]
+fn median_estimate() {
+    let mut rng = Rng::new(1);
+    let mut median = Median::default();
+    for _ in 0..100_000 {
+        median.push(rng.between(0, 1_000_000) as f64);
+    }
+    assert!(
+        (median.get() - 500_000.0).abs() < 10_000.0,
+        "{}",
+        median.get()
+    );
+}
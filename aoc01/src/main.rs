@@ -1,47 +1,37 @@
-use std::error::Error;
-use std::io::{self, Read, Write};
+use std::env;
+use std::io::{self, Write};
 
-#[allow(unused_macros)]
-macro_rules! err {
-    ($($tt:tt)*) => { Err(Box::<dyn Error>::from(format!($($tt)*))) }
-}
-
-type Result<T> = ::std::result::Result<T, Box<dyn Error>>;
+use aoc01::{part1, read_calories, top};
+use aoc_common::{parse_err, Result};
 
+/// Streams the elves from stdin, `aoc01 K` also adds up the top `K` instead of the top 3.
 fn main() -> Result<()> {
-    let mut input = String::new();
-    io::stdin().read_to_string(&mut input)?;
-    let mut elves_calories: Vec<i32> = vec![0];
-    let mut last = 0;
-    for line in input.lines() {
-        if line.is_empty() {
-            elves_calories.push(0);
-            last += 1;
-        } else {
-            elves_calories[last] += line.parse::<i32>().unwrap();
-        }
-    }
-    elves_calories.sort_by(|a, b| b.cmp(a));
-
-    part1(&elves_calories)?;
-    part2(&elves_calories)?;
-    Ok(())
-}
+    let k = match env::args().nth(1) {
+        Some(k) => match k.parse::<usize>() {
+            Ok(k) if k > 0 => k,
+            _ => return parse_err!("K must be a positive number, not {k:?}"),
+        },
+        None => 3,
+    };
+    let elves = read_calories(io::stdin().lock(), k)?;
 
-fn part1(elves_calories: &[i32]) -> Result<()> {
     writeln!(
         io::stdout(),
         "How many total Calories is that Elf carrying? {}",
-        elves_calories[0]
+        part1(&elves)?
     )?;
-    Ok(())
-}
-
-fn part2(elves_calories: &[i32]) -> Result<()> {
     writeln!(
         io::stdout(),
         "How many Calories are those Elves carrying in total? {}",
-        elves_calories[0..3].iter().sum::<i32>()
+        top(&elves, k)?
+    )?;
+    writeln!(
+        io::stdout(),
+        "> {} elves, mean {:.1}, median {:.1}, {} more tied with the top {k}",
+        elves.count,
+        elves.mean,
+        elves.median,
+        elves.ties
     )?;
     Ok(())
 }
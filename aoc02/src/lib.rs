@@ -0,0 +1,570 @@
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+use aoc_common::{parse_err, Answer, Error, Generator, Knobs, Position, Result, Rng, Solution};
+
+pub struct Day02;
+
+impl Solution for Day02 {
+    type Input = (Game, Guide);
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        let game = Game::classic();
+        let guide = game.parse_guide(input)?;
+        Ok((game, guide))
+    }
+
+    fn part1((game, guide): &Self::Input) -> Result<Answer> {
+        part1(game, guide).map(Answer::from)
+    }
+
+    fn part2((game, guide): &Self::Input) -> Result<Answer> {
+        part2(game, guide).map(Answer::from)
+    }
+
+    fn generator() -> Option<Generator> {
+        Some(Generator {
+            size: "rounds",
+            default_size: 2500,
+            knobs: &[],
+            generate,
+        })
+    }
+}
+
+/// The rules of the puzzle, in the format read by [`Game::from_str`].
+pub const CLASSIC: &str = "\
+shape Rock A X 1
+shape Paper B Y 2
+shape Scissors C Z 3
+outcome lose X 0
+outcome draw Y 3
+outcome win Z 6
+";
+
+/// The second column read as the shape to play.
+pub fn part1(game: &Game, guide: &Guide) -> Result<i64> {
+    game.play_shapes(guide)
+}
+
+/// The second column read as how the round must end.
+pub fn part2(game: &Game, guide: &Guide) -> Result<i64> {
+    game.play_outcomes(guide)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Lose,
+    Draw,
+    Win,
+}
+
+impl Outcome {
+    pub const ALL: [Outcome; 3] = [Outcome::Lose, Outcome::Draw, Outcome::Win];
+}
+
+impl FromStr for Outcome {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Ok(match s {
+            "lose" => Outcome::Lose,
+            "draw" => Outcome::Draw,
+            "win" => Outcome::Win,
+            _ => return parse_err!("{:?} is not an outcome, use lose, draw or win", s),
+        })
+    }
+}
+
+impl Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Outcome::Lose => "lose",
+            Outcome::Draw => "draw",
+            Outcome::Win => "win",
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Shape {
+    pub name: String,
+    /// The letter of the shape in the first column of the guide.
+    pub opponent: char,
+    /// The letter of the shape in the second column when it is read as shapes.
+    pub player: char,
+    pub score: i64,
+}
+
+/// A game of any number of shapes, where every two different shapes have a winner.
+///
+/// Read from lines, `#` starts a comment:
+///
+/// ```text
+/// shape <name> <opponent letter> <player letter> <score>
+/// outcome <lose|draw|win> <letter> <score>
+/// <name> beats <name>, <name>...
+/// ```
+///
+/// Without any `beats` line the game is cyclic: each shape beats the half of the shapes
+/// listed just before it, wrapping around, so the number of shapes must be odd. Rock, Paper,
+/// Scissors is cyclic, and so is Rock, Spock, Paper, Lizard, Scissors.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Game {
+    pub shapes: Vec<Shape>,
+    /// The letter and the score of each outcome, in the order of [`Outcome::ALL`].
+    pub outcomes: [(char, i64); 3],
+    /// `beats[a][b]` when the shape `a` wins against the shape `b`.
+    beats: Vec<Vec<bool>>,
+}
+
+impl Game {
+    pub fn classic() -> Game {
+        CLASSIC.parse().expect("the classic rules are valid")
+    }
+
+    /// The outcome for the player playing `player` against `opponent`, both shape indices.
+    pub fn outcome(&self, player: usize, opponent: usize) -> Outcome {
+        if self.beats[player][opponent] {
+            Outcome::Win
+        } else if self.beats[opponent][player] {
+            Outcome::Lose
+        } else {
+            Outcome::Draw
+        }
+    }
+
+    pub fn score(&self, player: usize, opponent: usize) -> i64 {
+        self.shapes[player].score + self.outcomes[self.outcome(player, opponent) as usize].1
+    }
+
+    /// The shape ending the round with `outcome`, the best scoring one when several do.
+    pub fn choose(&self, opponent: usize, outcome: Outcome) -> Option<usize> {
+        (0..self.shapes.len())
+            .filter(|&player| self.outcome(player, opponent) == outcome)
+            .max_by_key(|&player| (self.shapes[player].score, std::cmp::Reverse(player)))
+    }
+
+    pub fn parse_guide(&self, input: &str) -> Result<Guide> {
+        let rounds = input
+            .lines()
+            .enumerate()
+            .map(|(i, l)| {
+                let Some((opponent, letter)) = l.split_once(' ') else {
+                    return parse_err!("{:?} is not a round", l).at_line(i + 1);
+                };
+                let Some(opponent) = self
+                    .shapes
+                    .iter()
+                    .position(|s| letter_is(opponent, s.opponent))
+                else {
+                    return parse_err!("{:?} is not a shape", opponent).at_line(i + 1);
+                };
+                let known = self.shapes.iter().map(|s| s.player);
+                let mut known = known.chain(self.outcomes.iter().map(|&(letter, _)| letter));
+                match known.find(|&c| letter_is(letter, c)) {
+                    Some(letter) => Ok((opponent, letter)),
+                    None => parse_err!("{:?} is not a shape or an outcome", letter).at_line(i + 1),
+                }
+            })
+            .collect::<Result<_>>()?;
+        Ok(Guide { rounds })
+    }
+
+    pub fn play_shapes(&self, guide: &Guide) -> Result<i64> {
+        guide
+            .rounds
+            .iter()
+            .enumerate()
+            .try_fold(0, |total, (i, &(opponent, letter))| {
+                let Some(player) = self.shapes.iter().position(|s| s.player == letter) else {
+                    return parse_err!("{:?} is not a shape", letter).at_line(i + 1);
+                };
+                Ok(total + self.score(player, opponent))
+            })
+    }
+
+    pub fn play_outcomes(&self, guide: &Guide) -> Result<i64> {
+        guide
+            .rounds
+            .iter()
+            .enumerate()
+            .try_fold(0, |total, (i, &(opponent, letter))| {
+                let Some(outcome) = self.outcomes.iter().position(|&(c, _)| c == letter) else {
+                    return parse_err!("{:?} is not an outcome", letter).at_line(i + 1);
+                };
+                let player = self
+                    .choose(opponent, Outcome::ALL[outcome])
+                    .expect("the rules are checked to have every outcome against every shape");
+                Ok(total + self.score(player, opponent))
+            })
+    }
+
+    /// The best scoring way to read the second column of the guide, either as shapes or as
+    /// outcomes, best first.
+    ///
+    /// Every way to give the letters different meanings is tried, skipping the ones that can
+    /// not beat the best found so far.
+    pub fn analyze(&self, guide: &Guide) -> Vec<Interpretation> {
+        let mut letters: Vec<char> = guide.rounds.iter().map(|&(_, letter)| letter).collect();
+        letters.sort_unstable();
+        letters.dedup();
+        // how often each opponent shape comes with each letter
+        let mut counts = vec![vec![0i64; self.shapes.len()]; letters.len()];
+        for &(opponent, letter) in &guide.rounds {
+            let l = letters
+                .binary_search(&letter)
+                .expect("every letter is listed");
+            counts[l][opponent] += 1;
+        }
+        let gains = |meanings: usize, score: &dyn Fn(usize, usize) -> i64| -> Vec<Vec<i64>> {
+            counts
+                .iter()
+                .map(|count| {
+                    (0..meanings)
+                        .map(|meaning| {
+                            let scores = count.iter().enumerate();
+                            scores
+                                .map(|(opponent, &n)| n * score(meaning, opponent))
+                                .sum()
+                        })
+                        .collect()
+                })
+                .collect()
+        };
+
+        let mut interpretations = vec![];
+        let as_shapes = gains(self.shapes.len(), &|player, opponent| {
+            self.score(player, opponent)
+        });
+        if let Some((shapes, score)) = best_assignment(&as_shapes) {
+            interpretations.push(Interpretation {
+                letters: letters.clone(),
+                meaning: Meaning::Shapes(shapes),
+                score,
+            });
+        }
+        let as_outcomes = gains(3, &|outcome, opponent| {
+            let player = self.choose(opponent, Outcome::ALL[outcome]);
+            self.score(player.expect("every outcome is possible"), opponent)
+        });
+        if let Some((outcomes, score)) = best_assignment(&as_outcomes) {
+            interpretations.push(Interpretation {
+                letters,
+                meaning: Meaning::Outcomes(outcomes.into_iter().map(|o| Outcome::ALL[o]).collect()),
+                score,
+            });
+        }
+        interpretations.sort_by_key(|i| std::cmp::Reverse(i.score));
+        interpretations
+    }
+
+    /// Checks that every two shapes have exactly one winner, and that every outcome can be
+    /// reached against every shape.
+    fn check(&self) -> Result<()> {
+        for (a, shape) in self.shapes.iter().enumerate() {
+            if self.beats[a][a] {
+                return parse_err!("{} beats itself", shape.name);
+            }
+            for (b, other) in self.shapes.iter().enumerate().skip(a + 1) {
+                match (self.beats[a][b], self.beats[b][a]) {
+                    (true, true) => {
+                        return parse_err!("{} and {} beat each other", shape.name, other.name)
+                    }
+                    (false, false) => {
+                        return parse_err!("nothing says who wins {} or {}", shape.name, other.name)
+                    }
+                    _ => {}
+                }
+            }
+            for outcome in Outcome::ALL {
+                if self.choose(a, outcome).is_none() {
+                    return parse_err!("nothing can {} against {}", outcome, shape.name);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn letter_is(s: &str, letter: char) -> bool {
+    let mut chars = s.chars();
+    chars.next() == Some(letter) && chars.next().is_none()
+}
+
+fn parse_letter(s: &str) -> Result<char> {
+    let mut chars = s.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Ok(c),
+        _ => parse_err!("{:?} is not a letter", s),
+    }
+}
+
+impl FromStr for Game {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut shapes: Vec<Shape> = vec![];
+        let mut outcomes = [None; 3];
+        let mut beats: Vec<(usize, &str)> = vec![];
+        for (i, line) in s.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            let words: Vec<&str> = line.split_whitespace().collect();
+            match words[..] {
+                [] => {}
+                ["shape", name, opponent, player, score] => {
+                    let shape = Shape {
+                        name: name.to_string(),
+                        opponent: parse_letter(opponent).at_line(i + 1)?,
+                        player: parse_letter(player).at_line(i + 1)?,
+                        score: score.parse().at_line(i + 1)?,
+                    };
+                    if let Some(other) = shapes.iter().find(|s| {
+                        s.name == shape.name
+                            || s.opponent == shape.opponent
+                            || s.player == shape.player
+                    }) {
+                        return parse_err!("{} has the name or a letter of {}", name, other.name)
+                            .at_line(i + 1);
+                    }
+                    shapes.push(shape);
+                }
+                ["outcome", outcome, letter, score] => {
+                    let outcome: Outcome = outcome.parse().at_line(i + 1)?;
+                    let letter = parse_letter(letter).at_line(i + 1)?;
+                    outcomes[outcome as usize] = Some((letter, score.parse().at_line(i + 1)?));
+                }
+                [_, "beats", ..] => beats.push((i + 1, line)),
+                _ => return parse_err!("{:?} is not a rule", line).at_line(i + 1),
+            }
+        }
+
+        let n = shapes.len();
+        if n == 0 {
+            return parse_err!("the game has no shapes");
+        }
+        for outcome in Outcome::ALL {
+            if outcomes[outcome as usize].is_none() {
+                return parse_err!("the game has no score to {outcome}");
+            }
+        }
+        let outcomes = outcomes.map(|outcome| outcome.expect("every outcome is checked"));
+        let [(a, _), (b, _), (c, _)] = outcomes;
+        if a == b || a == c || b == c {
+            return parse_err!("two outcomes have the same letter");
+        }
+
+        let mut table = vec![vec![false; n]; n];
+        if beats.is_empty() {
+            if n.is_multiple_of(2) {
+                return parse_err!("a cyclic game needs an odd number of shapes, not {n}");
+            }
+            for (a, row) in table.iter_mut().enumerate() {
+                for d in 1..=n / 2 {
+                    row[(a + n - d) % n] = true;
+                }
+            }
+        }
+        let shape = |name: &str| match shapes.iter().position(|s| s.name == name) {
+            Some(s) => Ok(s),
+            None => parse_err!("{:?} is not a shape", name),
+        };
+        for (line_number, line) in beats {
+            let (winner, losers) = line.split_once(" beats ").unwrap_or((line, ""));
+            let winner = shape(winner.trim()).at_line(line_number)?;
+            for loser in losers.split(',') {
+                let loser = shape(loser.trim()).at_line(line_number)?;
+                table[winner][loser] = true;
+            }
+        }
+
+        let game = Game {
+            shapes,
+            outcomes,
+            beats: table,
+        };
+        game.check()?;
+        Ok(game)
+    }
+}
+
+/// The rounds of the encrypted strategy guide: the opponent shape and the letter of the
+/// second column, which means a shape or an outcome depending on the part.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Guide {
+    pub rounds: Vec<(usize, char)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Meaning {
+    Shapes(Vec<usize>),
+    Outcomes(Vec<Outcome>),
+}
+
+/// A way to read the second column of the guide and the total score it gives.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Interpretation {
+    /// The letters of the second column, each meaning the item at the same index of `meaning`.
+    pub letters: Vec<char>,
+    pub meaning: Meaning,
+    pub score: i64,
+}
+
+impl Interpretation {
+    pub fn describe(&self, game: &Game) -> String {
+        let meanings: Vec<String> = match &self.meaning {
+            Meaning::Shapes(shapes) => shapes
+                .iter()
+                .map(|&s| game.shapes[s].name.clone())
+                .collect(),
+            Meaning::Outcomes(outcomes) => outcomes.iter().map(Outcome::to_string).collect(),
+        };
+        let pairs: Vec<String> = self
+            .letters
+            .iter()
+            .zip(meanings)
+            .map(|(letter, meaning)| format!("{letter} = {meaning}"))
+            .collect();
+        format!("{} scores {}", pairs.join(", "), self.score)
+    }
+}
+
+/// Gives each row a different column, maximizing the sum of `gains[row][column]`.
+///
+/// `None` when there are more rows than columns.
+fn best_assignment(gains: &[Vec<i64>]) -> Option<(Vec<usize>, i64)> {
+    fn search(
+        gains: &[Vec<i64>],
+        bounds: &[i64],
+        taken: &mut Vec<bool>,
+        current: &mut Vec<usize>,
+        total: i64,
+        best: &mut Option<(Vec<usize>, i64)>,
+    ) {
+        let row = current.len();
+        if row == gains.len() {
+            if best.as_ref().is_none_or(|(_, score)| total > *score) {
+                *best = Some((current.clone(), total));
+            }
+            return;
+        }
+        // even the best column of every remaining row would not do better
+        if best
+            .as_ref()
+            .is_some_and(|(_, score)| total + bounds[row] <= *score)
+        {
+            return;
+        }
+        for (column, &gain) in gains[row].iter().enumerate() {
+            if !taken[column] {
+                taken[column] = true;
+                current.push(column);
+                search(gains, bounds, taken, current, total + gain, best);
+                current.pop();
+                taken[column] = false;
+            }
+        }
+    }
+
+    let columns = gains.first().map_or(0, Vec::len);
+    if gains.len() > columns {
+        return None;
+    }
+    let mut bounds = vec![0; gains.len() + 1];
+    for row in (0..gains.len()).rev() {
+        bounds[row] = bounds[row + 1] + gains[row].iter().max().copied().unwrap_or(0);
+    }
+    let mut best = None;
+    let mut taken = vec![false; columns];
+    search(gains, &bounds, &mut taken, &mut vec![], 0, &mut best);
+    best
+}
+
+fn generate(rng: &mut Rng, rounds: usize, _knobs: &Knobs) -> Result<String> {
+    let rounds: Vec<String> = (0..rounds.max(1))
+        .map(|_| {
+            format!(
+                "{} {}",
+                rng.choose(&['A', 'B', 'C']),
+                rng.choose(&['X', 'Y', 'Z'])
+            )
+        })
+        .collect();
+    Ok(rounds.join("\n"))
+}
+
+#[test]
+fn example_input() {
+    let input = "A Y
+B X
+C Z";
+    let (game, guide) = Day02::parse(input).unwrap();
+    assert_eq!(part1(&game, &guide).unwrap(), 15);
+    assert_eq!(part2(&game, &guide).unwrap(), 12);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RPSLS: &str = include_str!("../rules/rpsls.txt");
+
+    #[test]
+    fn rock_paper_scissors_lizard_spock() {
+        let cyclic = "\
+            shape Rock A V 1\nshape Spock B W 2\nshape Paper C X 3\n\
+            shape Lizard D Y 4\nshape Scissors E Z 5\n\
+            outcome lose X 0\noutcome draw Y 3\noutcome win Z 6\n";
+        let game: Game = RPSLS.parse().unwrap();
+        assert_eq!(game, cyclic.parse::<Game>().unwrap());
+        let guide = game.parse_guide("A W\nD Z\nE V\nB Y").unwrap();
+        // Spock vaporizes Rock, Scissors decapitate Lizard, Rock crushes Scissors,
+        // Lizard poisons Spock
+        assert_eq!(game.play_shapes(&guide).unwrap(), 8 + 11 + 7 + 10);
+        // losing to Rock is best with Scissors, drawing Lizard, winning against Scissors is
+        // best with Spock, winning against Spock with Lizard
+        let guide = game.parse_guide("A X\nD Y\nE Z\nB Z").unwrap();
+        assert_eq!(game.play_outcomes(&guide).unwrap(), 5 + 7 + 8 + 10);
+    }
+
+    #[test]
+    fn invalid_rules() {
+        let outcomes = "outcome lose X 0\noutcome draw Y 3\noutcome win Z 6\n";
+        let two = format!("shape Rock A X 1\nshape Paper B Y 2\n{outcomes}");
+        assert!(two.parse::<Game>().is_err());
+        let undecided = format!("{two}shape Well C Z 3\nPaper beats Rock, Well\n");
+        assert!(undecided.parse::<Game>().is_err());
+        let both = format!("{two}Paper beats Rock\nRock beats Paper");
+        assert!(both.parse::<Game>().is_err());
+        let explicit =
+            format!("{two}shape Well C Z 3\nPaper beats Rock\nWell beats Paper\nRock beats Well");
+        assert!(explicit.parse::<Game>().is_ok());
+        assert!("shape Rock A X 1\nshape Paper B X 2"
+            .parse::<Game>()
+            .is_err());
+        let err = format!("{outcomes}shape Rock A X one")
+            .parse::<Game>()
+            .unwrap_err();
+        assert!(err.to_string().contains("line 4"), "{err}");
+    }
+
+    #[test]
+    fn analyze_guide() {
+        let game = Game::classic();
+        let guide = game.parse_guide("A Y\nB X\nC Z").unwrap();
+        let best = &game.analyze(&guide)[0];
+        // winning every round is best, and there is a shape winning each one
+        assert_eq!(best.score, 8 + 9 + 7);
+        assert_eq!(
+            best.describe(&game),
+            "X = Scissors, Y = Paper, Z = Rock scores 24"
+        );
+        let guide = game.parse_guide(&"A X\n".repeat(5)).unwrap();
+        let analysis = game.analyze(&guide);
+        // playing Paper and winning are the same
+        assert_eq!(analysis[0].meaning, Meaning::Shapes(vec![1]));
+        assert_eq!(analysis[1].meaning, Meaning::Outcomes(vec![Outcome::Win]));
+        assert_eq!(analysis[0].score, 5 * 8);
+        assert_eq!(analysis[1].score, 5 * 8);
+    }
+}
@@ -0,0 +1,461 @@
+use std::collections::HashMap;
+
+use aoc_common::{parse_err, Answer, Generator, Knobs, Position, Result, Rng, Solution, Variant};
+
+pub struct Day03;
+
+impl Solution for Day03 {
+    type Input = Vec<Vec<usize>>;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_input(input)
+    }
+
+    fn part1(rucksacks: &Self::Input) -> Result<Answer> {
+        part1(rucksacks).map(Answer::from)
+    }
+
+    fn part2(rucksacks: &Self::Input) -> Result<Answer> {
+        part2(rucksacks).map(Answer::from)
+    }
+
+    fn variants() -> Vec<Variant> {
+        vec![
+            Variant {
+                name: "bits",
+                part: 1,
+                solve: |input| part1_with_bits(input).map(Answer::from),
+            },
+            Variant {
+                name: "bits",
+                part: 2,
+                solve: |input| part2_with_bits(input).map(Answer::from),
+            },
+        ]
+    }
+
+    fn generator() -> Option<Generator> {
+        Some(Generator {
+            size: "groups of three elves",
+            default_size: 100,
+            knobs: &[],
+            generate,
+        })
+    }
+}
+
+fn priority(b: u8) -> Result<usize> {
+    match b {
+        b'a'..=b'z' => Ok((b - b'a' + 1) as usize),
+        b'A'..=b'Z' => Ok((b - b'A' + 27) as usize),
+        _ => parse_err!("{:?} is not an item", b as char),
+    }
+}
+
+pub fn parse_input(input: &str) -> Result<Vec<Vec<usize>>> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| {
+            if !line.len().is_multiple_of(2) {
+                return parse_err!(
+                    "the two compartments of {:?} do not have the same size",
+                    line
+                )
+                .at_line(i + 1);
+            }
+            line.bytes()
+                .enumerate()
+                .map(|(j, b)| priority(b).at(i + 1, j + 1))
+                .collect()
+        })
+        .collect()
+}
+
+fn check_groups(count: usize) -> Result<()> {
+    if !count.is_multiple_of(3) {
+        return parse_err!("{count} elves can not be split into groups of three");
+    }
+    Ok(())
+}
+
+pub fn part1(rucksacks: &[Vec<usize>]) -> Result<usize> {
+    let mut sum = 0;
+    for items in rucksacks {
+        let l = items.len();
+        let mut dup = [(false, false); 53];
+        for i in 0..l / 2 {
+            dup[items[i]].0 = true;
+        }
+        for i in l / 2..l {
+            dup[items[i]].1 = true;
+        }
+        sum += dup
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| c == &&(true, true))
+            .map(|(i, _)| i)
+            .sum::<usize>();
+    }
+    Ok(sum)
+}
+
+pub fn part2(rucksacks: &[Vec<usize>]) -> Result<usize> {
+    check_groups(rucksacks.len())?;
+    let mut sum = 0;
+    for group in rucksacks.chunks(3) {
+        let mut dup = [(false, false, false); 53];
+        for &i in &group[0] {
+            dup[i].0 = true;
+        }
+        for &i in &group[1] {
+            dup[i].1 = true;
+        }
+        for &i in &group[2] {
+            dup[i].2 = true;
+        }
+        sum += dup
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| c == &&(true, true, true))
+            .map(|(i, _)| i)
+            .sum::<usize>();
+    }
+    Ok(sum)
+}
+
+/// The item types of the rucksacks in the order of their priorities, the first one has the
+/// priority 1.
+///
+/// Any `char` but a line ending can be an item, and there can be any number of them.
+#[derive(Debug, Clone)]
+pub struct Alphabet {
+    items: Vec<char>,
+    /// The index of each ASCII item, `u32::MAX` for the ones not in the alphabet.
+    ascii: [u32; 128],
+    others: HashMap<char, usize>,
+}
+
+impl Alphabet {
+    pub fn new(items: &str) -> Result<Alphabet> {
+        let mut alphabet = Alphabet {
+            items: vec![],
+            ascii: [u32::MAX; 128],
+            others: HashMap::new(),
+        };
+        for item in items.chars() {
+            if item == '\n' || item == '\r' {
+                return parse_err!("a line ending can not be an item");
+            }
+            if alphabet.index(item).is_some() {
+                return parse_err!("{:?} is twice in the alphabet", item);
+            }
+            alphabet.push(item);
+        }
+        Ok(alphabet)
+    }
+
+    /// The alphabet of the puzzle, `a` to `z` then `A` to `Z`.
+    pub fn classic() -> Alphabet {
+        Alphabet::new(std::str::from_utf8(ITEMS).unwrap()).expect("the letters are different")
+    }
+
+    /// Every item of `input`, in the order they first appear.
+    pub fn of(input: &str) -> Alphabet {
+        let mut alphabet = Alphabet::new("").expect("an empty alphabet is valid");
+        for item in input.chars() {
+            if item != '\n' && item != '\r' && alphabet.index(item).is_none() {
+                alphabet.push(item);
+            }
+        }
+        alphabet
+    }
+
+    fn push(&mut self, item: char) {
+        let index = self.items.len();
+        match u8::try_from(item) {
+            Ok(b) if b.is_ascii() => self.ascii[b as usize] = index as u32,
+            _ => {
+                self.others.insert(item, index);
+            }
+        }
+        self.items.push(item);
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    fn index(&self, item: char) -> Option<usize> {
+        match u8::try_from(item) {
+            Ok(b) if b.is_ascii() => {
+                Some(self.ascii[b as usize] as usize).filter(|&i| i != u32::MAX as usize)
+            }
+            _ => self.others.get(&item).copied(),
+        }
+    }
+
+    pub fn priority(&self, item: char) -> Option<usize> {
+        self.index(item).map(|i| i + 1)
+    }
+
+    /// The set of the items of `s`, which starts at `line` and `column` of the input.
+    pub fn items(&self, s: &str, line: usize, column: usize) -> Result<ItemSet> {
+        let mut set = ItemSet::new(self.len());
+        for (j, item) in s.chars().enumerate() {
+            match self.index(item) {
+                Some(i) => set.insert(i),
+                None => return parse_err!("{:?} is not an item", item).at(line, column + j),
+            }
+        }
+        Ok(set)
+    }
+
+    /// The items of `set`, by priority.
+    pub fn list(&self, set: &ItemSet) -> Vec<char> {
+        set.iter().map(|i| self.items[i]).collect()
+    }
+
+    /// The sum of the priorities of every item in every list.
+    pub fn priorities(&self, lists: &[Vec<char>]) -> usize {
+        lists
+            .iter()
+            .flatten()
+            .map(|&item| {
+                self.priority(item)
+                    .expect("the items come from the alphabet")
+            })
+            .sum()
+    }
+}
+
+/// A set of the indices of the items of an [`Alphabet`], one bit per item.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ItemSet {
+    words: Vec<u64>,
+}
+
+impl ItemSet {
+    pub fn new(len: usize) -> ItemSet {
+        ItemSet {
+            words: vec![0; len.div_ceil(64)],
+        }
+    }
+
+    pub fn insert(&mut self, i: usize) {
+        self.words[i / 64] |= 1 << (i % 64);
+    }
+
+    pub fn contains(&self, i: usize) -> bool {
+        self.words[i / 64] & (1 << (i % 64)) != 0
+    }
+
+    /// Keeps the items of `other` only, a word at a time so that it gets vectorized.
+    pub fn intersect(&mut self, other: &ItemSet) {
+        for (word, other) in self.words.iter_mut().zip(&other.words) {
+            *word &= other;
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words.iter().enumerate().flat_map(|(w, &word)| {
+            let mut word = word;
+            std::iter::from_fn(move || {
+                if word == 0 {
+                    return None;
+                }
+                let bit = word.trailing_zeros() as usize;
+                word &= word - 1;
+                Some(w * 64 + bit)
+            })
+        })
+    }
+}
+
+/// Every item in both compartments of each rucksack.
+pub fn shared_items(alphabet: &Alphabet, input: &str) -> Result<Vec<Vec<char>>> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| {
+            let len = line.chars().count();
+            if !len.is_multiple_of(2) {
+                return parse_err!(
+                    "the two compartments of {:?} do not have the same size",
+                    line
+                )
+                .at_line(i + 1);
+            }
+            let middle = line.char_indices().nth(len / 2).map_or(0, |(j, _)| j);
+            let mut items = alphabet.items(&line[..middle], i + 1, 1)?;
+            let right = alphabet.items(&line[middle..], i + 1, len / 2 + 1)?;
+            items.intersect(&right);
+            Ok(alphabet.list(&items))
+        })
+        .collect()
+}
+
+/// Every item carried by all the elves of each group of `size` elves.
+pub fn badges(alphabet: &Alphabet, input: &str, size: usize) -> Result<Vec<Vec<char>>> {
+    if size == 0 {
+        return parse_err!("a group has at least one elf");
+    }
+    let lines: Vec<_> = input.lines().collect();
+    if !lines.len().is_multiple_of(size) {
+        return parse_err!(
+            "{} elves can not be split into groups of {size}",
+            lines.len()
+        );
+    }
+    lines
+        .chunks(size)
+        .enumerate()
+        .map(|(g, group)| {
+            let mut items = alphabet.items(group[0], g * size + 1, 1)?;
+            for (i, rucksack) in group.iter().enumerate().skip(1) {
+                items.intersect(&alphabet.items(rucksack, g * size + i + 1, 1)?);
+            }
+            Ok(alphabet.list(&items))
+        })
+        .collect()
+}
+
+pub fn part1_with_bits(input: &str) -> Result<usize> {
+    let alphabet = Alphabet::classic();
+    Ok(alphabet.priorities(&shared_items(&alphabet, input)?))
+}
+
+pub fn part2_with_bits(input: &str) -> Result<usize> {
+    let alphabet = Alphabet::classic();
+    Ok(alphabet.priorities(&badges(&alphabet, input, 3)?))
+}
+
+const ITEMS: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+/// A rucksack with `shared` in both compartments and `badge` in one of them, the other items are
+/// taken from `others` and only ever in one compartment.
+fn rucksack(rng: &mut Rng, shared: u8, badge: u8, mut others: Vec<u8>) -> Vec<u8> {
+    rng.shuffle(&mut others);
+    others.truncate(rng.between(2, 16) as usize);
+    let split = rng.index(others.len() + 1);
+    let mut left = vec![shared];
+    left.extend(&others[..split]);
+    let mut right = vec![shared];
+    right.extend(&others[split..]);
+    if badge != shared {
+        if rng.chance(0.5) {
+            &mut left
+        } else {
+            &mut right
+        }
+        .push(badge);
+    }
+    let len = left.len().max(right.len()) + rng.between(0, 4) as usize;
+    for half in [&mut left, &mut right] {
+        while half.len() < len {
+            half.push(*rng.choose(half));
+        }
+        rng.shuffle(half);
+    }
+    left.extend(right);
+    left
+}
+
+fn generate(rng: &mut Rng, groups: usize, _knobs: &Knobs) -> Result<String> {
+    let mut lines = vec![];
+    for _ in 0..groups.max(1) {
+        let badge = *rng.choose(ITEMS);
+        let mut group: Vec<Vec<u8>> = vec![];
+        for _ in 0..3 {
+            // the third elf must not have an item the two others have, but the badge
+            let allowed: Vec<u8> = ITEMS
+                .iter()
+                .copied()
+                .filter(|&i| i == badge || group.len() < 2 || !group.iter().all(|r| r.contains(&i)))
+                .collect();
+            let shared = *rng.choose(&allowed);
+            let others = allowed
+                .into_iter()
+                .filter(|&i| i != badge && i != shared)
+                .collect();
+            group.push(rucksack(rng, shared, badge, others));
+        }
+        lines.extend(
+            group
+                .into_iter()
+                .map(|r| String::from_utf8_lossy(&r).into_owned()),
+        );
+    }
+    Ok(lines.join("\n"))
+}
+
+#[test]
+fn example_input() {
+    let input = "vJrwpWtwJgWrhcsFMMfFFhFp
+jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL
+PmmdzqPrVvPwwTWBwg
+wMqvLMZHhHMvwLHjbvcjnnSBnvTQFn
+ttgJtRGJQctTZtZT
+CrZsJsPPZsGzwwsLwLmpwMDw";
+    let rucksacks = parse_input(input).unwrap();
+    assert_eq!(part1(&rucksacks).unwrap(), 157);
+    assert_eq!(part2(&rucksacks).unwrap(), 70);
+    assert_eq!(part1_with_bits(input).unwrap(), 157);
+    assert_eq!(part2_with_bits(input).unwrap(), 70);
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    /// A rucksack of any letters, the two compartments of the same size.
+    fn rucksack() -> impl Strategy<Value = String> {
+        "([a-zA-Z]{2}){1,24}"
+    }
+
+    #[test]
+    fn unicode_items() {
+        // more than 64 items, so that the sets take two words
+        let items: String = ('一'..='龥').take(100).collect();
+        let alphabet = Alphabet::new(&items).unwrap();
+        let input = "一七丁七\n丁丂丂丁\n七丁丄丅\n丁七丂七";
+        let shared = shared_items(&alphabet, input).unwrap();
+        assert_eq!(shared, [vec!['七'], vec!['丁', '丂'], vec![], vec!['七']]);
+        assert_eq!(badges(&alphabet, input, 4).unwrap(), [vec!['丁']]);
+        assert_eq!(badges(&alphabet, input, 2).unwrap()[1], ['丁', '七']);
+        let last = alphabet.items(&items[items.len() - 3..], 1, 1).unwrap();
+        assert_eq!(last.iter().collect::<Vec<_>>(), [99]);
+        assert_eq!(alphabet.priority('丁'), Some(2));
+        let err = badges(&alphabet, "一\n一a", 2).unwrap_err();
+        assert!(err.to_string().contains("line 2, column 2"), "{err}");
+        assert!(badges(&alphabet, input, 3).is_err());
+        assert!(Alphabet::new("abca").is_err());
+    }
+
+    #[test]
+    fn learned_alphabet() {
+        let input = "xyyx\nzzyw";
+        let alphabet = Alphabet::of(input);
+        assert_eq!(alphabet.len(), 4);
+        assert_eq!(alphabet.priority('w'), Some(4));
+        assert_eq!(badges(&alphabet, input, 2).unwrap(), [vec!['y']]);
+    }
+
+    proptest! {
+        #[test]
+        fn bits_agree_with_arrays(
+            groups in prop::collection::vec([rucksack(), rucksack(), rucksack()], 0..20),
+        ) {
+            let input = groups.concat().join("\n");
+            let rucksacks = parse_input(&input).unwrap();
+            prop_assert_eq!(part1(&rucksacks).unwrap(), part1_with_bits(&input).unwrap());
+            prop_assert_eq!(part2(&rucksacks).unwrap(), part2_with_bits(&input).unwrap());
+        }
+    }
+}
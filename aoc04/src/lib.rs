@@ -0,0 +1,279 @@
+use std::str::FromStr;
+
+use aoc_common::{
+    parse_err, Answer, Error, Generator, Knob, Knobs, Position, Result, Rng, Solution, Variant,
+};
+use aoc_interval::{deepest, IntervalSet};
+
+pub struct Day04;
+
+impl Solution for Day04 {
+    type Input = Vec<(Range, Range)>;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_input(input)
+    }
+
+    fn part1(pairs: &Self::Input) -> Result<Answer> {
+        part1(pairs).map(Answer::from)
+    }
+
+    fn part2(pairs: &Self::Input) -> Result<Answer> {
+        part2(pairs).map(Answer::from)
+    }
+
+    fn variants() -> Vec<Variant> {
+        vec![
+            Variant {
+                name: "pair struct",
+                part: 1,
+                solve: |input| part1_with_pair_struct(&parse_pairs(input)?).map(Answer::from),
+            },
+            Variant {
+                name: "pair struct",
+                part: 2,
+                solve: |input| part2_with_pair_struct(&parse_pairs(input)?).map(Answer::from),
+            },
+            Variant {
+                name: "interval set",
+                part: 1,
+                solve: |input| part1_with_interval_set(&parse_input(input)?).map(Answer::from),
+            },
+            Variant {
+                name: "interval set",
+                part: 2,
+                solve: |input| part2_with_interval_set(&parse_input(input)?).map(Answer::from),
+            },
+        ]
+    }
+
+    fn generator() -> Option<Generator> {
+        Some(Generator {
+            size: "pairs",
+            default_size: 1000,
+            knobs: &[Knob {
+                name: "sections",
+                about: "number of sections",
+                default: "99",
+            }],
+            generate,
+        })
+    }
+}
+
+pub type Range = (i32, i32);
+
+pub fn parse_input(input: &str) -> Result<Vec<(Range, Range)>> {
+    let pairs = parse_pairs(input)?;
+    Ok(pairs.into_iter().map(|p| (p.first, p.second)).collect())
+}
+
+pub fn parse_pairs(input: &str) -> Result<Vec<Pair>> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, l)| l.parse().at_line(i + 1))
+        .collect()
+}
+
+pub fn part1(pairs: &[(Range, Range)]) -> Result<usize> {
+    let count = pairs
+        .iter()
+        .filter(|(p1, p2)| fully_contain(p1, p2))
+        .count();
+    Ok(count)
+}
+
+pub fn part2(pairs: &[(Range, Range)]) -> Result<usize> {
+    let count = pairs.iter().filter(|(p1, p2)| overlap(p1, p2)).count();
+    Ok(count)
+}
+
+fn fully_contain(p1: &Range, p2: &Range) -> bool {
+    (p1.0 <= p2.0 && p1.1 >= p2.1) || (p1.0 >= p2.0 && p1.1 <= p2.1)
+
+    // let (mut p1, mut p2) = (p1, p2);
+    // if p1.0 > p2.0 {
+    //     (p1, p2) = (p2, p1);
+    // }
+    // if p1.0 == p2.0 && p1.1 < p2.1 {
+    //     (p1, p2) = (p2, p1);
+    // }
+    // p2.1 <= p1.1
+}
+
+fn overlap(p1: &Range, p2: &Range) -> bool {
+    let (mut p1, mut p2) = (p1, p2);
+    if p1.0 > p2.0 {
+        (p1, p2) = (p2, p1);
+    }
+    p2.0 <= p1.1
+}
+
+pub fn part1_with_pair_struct(pairs: &[Pair]) -> Result<usize> {
+    let count = pairs.iter().filter(|p| p.fully_contain()).count();
+    Ok(count)
+}
+
+pub fn part2_with_pair_struct(pairs: &[Pair]) -> Result<usize> {
+    let count = pairs.iter().filter(|p| p.overlap()).count();
+    Ok(count)
+}
+
+fn sections(range: &Range) -> IntervalSet {
+    IntervalSet::from_iter([(i64::from(range.0), i64::from(range.1))])
+}
+
+pub fn part1_with_interval_set(pairs: &[(Range, Range)]) -> Result<usize> {
+    let count = pairs
+        .iter()
+        .filter(|(p1, p2)| {
+            let (s1, s2) = (sections(p1), sections(p2));
+            let both = s1.intersection(&s2);
+            both == s1 || both == s2
+        })
+        .count();
+    Ok(count)
+}
+
+pub fn part2_with_interval_set(pairs: &[(Range, Range)]) -> Result<usize> {
+    let count = pairs
+        .iter()
+        .filter(|(p1, p2)| !sections(p1).intersection(&sections(p2)).is_empty())
+        .count();
+    Ok(count)
+}
+
+/// The sections from 1 to the last one assigned that no elf has to clean up.
+pub fn unassigned(pairs: &[(Range, Range)]) -> IntervalSet {
+    let assigned: IntervalSet = pairs
+        .iter()
+        .flat_map(|(p1, p2)| [p1, p2])
+        .map(|&(start, end)| (i64::from(start), i64::from(end)))
+        .collect();
+    let last = assigned.intervals().last().map_or(0, |&(_, end)| end);
+    assigned.gaps_within((1, last))
+}
+
+/// The sections assigned to the most elves, with how many elves have them.
+pub fn most_assigned(pairs: &[(Range, Range)]) -> Option<(IntervalSet, usize)> {
+    deepest(
+        pairs
+            .iter()
+            .flat_map(|(p1, p2)| [p1, p2])
+            .map(|&(start, end)| (i64::from(start), i64::from(end))),
+    )
+}
+
+pub struct Pair {
+    first: Range,
+    second: Range,
+}
+
+impl FromStr for Pair {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Pair> {
+        if let Some((first, last)) = s.split_once(',') {
+            let first: Range = if let Some((start, end)) = first.split_once('-') {
+                (start.parse()?, end.parse()?)
+            } else {
+                return parse_err!("This is not a valid pair: {:?}", s);
+            };
+            let second: Range = if let Some((start, end)) = last.split_once('-') {
+                (start.parse()?, end.parse()?)
+            } else {
+                return parse_err!("This is not a valid pair: {:?}", s);
+            };
+            if first.0 > first.1 || second.0 > second.1 {
+                return parse_err!("This pair has an empty range: {:?}", s);
+            }
+            Ok(Pair { first, second })
+        } else {
+            parse_err!("This is not a pair: {:?}", s)
+        }
+    }
+}
+
+impl Pair {
+    pub fn fully_contain(&self) -> bool {
+        let (p1, p2) = (self.first, self.second);
+        (p1.0 <= p2.0 && p1.1 >= p2.1) || (p1.0 >= p2.0 && p1.1 <= p2.1)
+    }
+
+    pub fn overlap(&self) -> bool {
+        let (p1, p2) = (self.first, self.second);
+        let (mut p1, mut p2) = (p1, p2);
+        if p1.0 > p2.0 {
+            (p1, p2) = (p2, p1);
+        }
+        p2.0 <= p1.1
+    }
+}
+
+fn generate(rng: &mut Rng, pairs: usize, knobs: &Knobs) -> Result<String> {
+    let sections: i64 = knobs.get::<i64>("sections")?.max(1);
+    let mut range = || {
+        let start = rng.between(1, sections);
+        (start, rng.between(start, sections))
+    };
+    let pairs: Vec<String> = (0..pairs.max(1))
+        .map(|_| {
+            let (first, second) = (range(), range());
+            format!("{}-{},{}-{}", first.0, first.1, second.0, second.1)
+        })
+        .collect();
+    Ok(pairs.join("\n"))
+}
+
+#[test]
+fn example_input() {
+    let input = "2-4,6-8
+2-3,4-5
+5-7,7-9
+2-8,3-7
+6-6,4-6
+2-6,4-8";
+    let pairs = parse_input(input).unwrap();
+    assert_eq!(part1(&pairs).unwrap(), 2);
+    assert_eq!(part2(&pairs).unwrap(), 4);
+    let pairs = parse_pairs(input).unwrap();
+    assert_eq!(part1_with_pair_struct(&pairs).unwrap(), 2);
+    assert_eq!(part2_with_pair_struct(&pairs).unwrap(), 4);
+    let pairs = parse_input(input).unwrap();
+    assert_eq!(part1_with_interval_set(&pairs).unwrap(), 2);
+    assert_eq!(part2_with_interval_set(&pairs).unwrap(), 4);
+    assert_eq!(unassigned(&pairs).to_string(), "1");
+    let (sections, elves) = most_assigned(&pairs).unwrap();
+    assert_eq!((sections.to_string(), elves), ("6".to_string(), 8));
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    fn range() -> impl Strategy<Value = Range> {
+        (0..100, 0..20).prop_map(|(start, len)| (start, start + len))
+    }
+
+    proptest! {
+        #[test]
+        fn pair_struct_agrees_with_tuples(
+            pairs in prop::collection::vec((range(), range()), 1..50),
+        ) {
+            let input = pairs
+                .iter()
+                .map(|(first, second)| format!("{}-{},{}-{}", first.0, first.1, second.0, second.1))
+                .collect::<Vec<_>>()
+                .join("\n");
+            let tuples = parse_input(&input).unwrap();
+            let structs = parse_pairs(&input).unwrap();
+            prop_assert_eq!(part1(&tuples).unwrap(), part1_with_pair_struct(&structs).unwrap());
+            prop_assert_eq!(part2(&tuples).unwrap(), part2_with_pair_struct(&structs).unwrap());
+            prop_assert_eq!(part1(&tuples).unwrap(), part1_with_interval_set(&tuples).unwrap());
+            prop_assert_eq!(part2(&tuples).unwrap(), part2_with_interval_set(&tuples).unwrap());
+        }
+    }
+}
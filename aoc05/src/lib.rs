@@ -0,0 +1,461 @@
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+use aoc_common::{
+    parse_err, Animation, Answer, Error, Generator, Knob, Knobs, Position, Result, Rng, Solution,
+};
+
+pub struct Day05;
+
+impl Solution for Day05 {
+    type Input = (Stacks, Vec<Step>);
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_input(input)
+    }
+
+    fn part1((stacks, steps): &Self::Input) -> Result<Answer> {
+        part1(stacks.clone(), steps).map(Answer::from)
+    }
+
+    fn part2((stacks, steps): &Self::Input) -> Result<Answer> {
+        part2(stacks.clone(), steps).map(Answer::from)
+    }
+
+    fn animations() -> Vec<Animation> {
+        vec![
+            Animation {
+                name: "CrateMover 9000",
+                part: 1,
+                animate: |input, screen| animate(&CrateMover9000, input, &mut |s| screen.show(s)),
+            },
+            Animation {
+                name: "CrateMover 9001",
+                part: 2,
+                animate: |input, screen| animate(&CrateMover9001, input, &mut |s| screen.show(s)),
+            },
+        ]
+    }
+
+    fn generator() -> Option<Generator> {
+        Some(Generator {
+            size: "steps of the procedure",
+            default_size: 500,
+            knobs: &[
+                Knob {
+                    name: "stacks",
+                    about: "number of stacks",
+                    default: "9",
+                },
+                Knob {
+                    name: "crates",
+                    about: "number of crates",
+                    default: "50",
+                },
+            ],
+            generate,
+        })
+    }
+}
+
+/// The stacks of crates, bottom first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Stacks(pub Vec<Vec<char>>);
+
+impl Stacks {
+    pub fn tops(&self) -> String {
+        self.0.iter().filter_map(|s| s.last()).collect()
+    }
+
+    pub fn height(&self) -> usize {
+        self.0.iter().map(Vec::len).max().unwrap_or(0)
+    }
+
+    /// Moves the crates of `step` with `crane`, if the stacks are there and have that many.
+    ///
+    /// A step that can not be done is an error of the input at the line of the step.
+    pub fn apply(&mut self, crane: &dyn Crane, step: &Step) -> Result<()> {
+        if step.from >= self.0.len() || step.to >= self.0.len() {
+            return parse_err!("there are only {} stacks", self.0.len()).at_line(step.line);
+        }
+        let len = self.0[step.from].len();
+        if len < step.count {
+            return parse_err!(
+                "can not move {} crates from stack {}, it has {len}",
+                step.count,
+                step.from + 1
+            )
+            .at_line(step.line);
+        }
+        crane.operate(&mut self.0, step);
+        Ok(())
+    }
+
+    /// Draws the stacks like the puzzle input, at least `height` crates high.
+    pub fn draw(&self, height: usize) -> String {
+        let mut lines = vec![];
+        for level in (0..height.max(self.height())).rev() {
+            let row: Vec<_> = self
+                .0
+                .iter()
+                .map(|s| s.get(level).map_or("   ".to_string(), |c| format!("[{c}]")))
+                .collect();
+            lines.push(row.join(" "));
+        }
+        let numbers: Vec<_> = (1..=self.0.len()).map(|n| format!("{n:^3}")).collect();
+        lines.push(numbers.join(" "));
+        lines.join("\n")
+    }
+}
+
+/// The drawing of the puzzle input.
+impl Display for Stacks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.draw(0))
+    }
+}
+
+/// A step of the procedure, `move 3 from 9 to 4`, with the stacks numbered from 0.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Step {
+    pub count: usize,
+    pub from: usize,
+    pub to: usize,
+    /// The line of the input, for the errors.
+    pub line: usize,
+}
+
+/// A crane model, moving the crates of a step from one stack to another.
+pub trait Crane {
+    /// `step.from` and `step.to` are stacks of `stacks`, and `stacks[step.from]` has at least
+    /// `step.count` crates.
+    fn operate(&self, stacks: &mut [Vec<char>], step: &Step);
+}
+
+/// Moves one crate at a time.
+pub struct CrateMover9000;
+
+impl Crane for CrateMover9000 {
+    fn operate(&self, stacks: &mut [Vec<char>], step: &Step) {
+        for _ in 0..step.count {
+            let c = stacks[step.from].pop().unwrap();
+            stacks[step.to].push(c);
+        }
+    }
+}
+
+/// Moves all the crates of a step at once, keeping their order.
+pub struct CrateMover9001;
+
+impl Crane for CrateMover9001 {
+    fn operate(&self, stacks: &mut [Vec<char>], step: &Step) {
+        let index = stacks[step.from].len() - step.count;
+        let lifted = stacks[step.from].split_off(index);
+        stacks[step.to].extend(lifted);
+    }
+}
+
+/// Moves up to `capacity` crates at once, keeping their order, the top ones first.
+///
+/// A capacity of 1 is the CrateMover 9000, a capacity as large as the stacks the 9001.
+pub struct LimitedCrane {
+    capacity: usize,
+}
+
+impl LimitedCrane {
+    pub fn new(capacity: usize) -> Result<Self> {
+        if capacity == 0 {
+            return parse_err!("a crane lifts at least one crate");
+        }
+        Ok(LimitedCrane { capacity })
+    }
+}
+
+impl Crane for LimitedCrane {
+    fn operate(&self, stacks: &mut [Vec<char>], step: &Step) {
+        let mut left = step.count;
+        while left > 0 {
+            let load = left.min(self.capacity);
+            let index = stacks[step.from].len() - load;
+            let lifted = stacks[step.from].split_off(index);
+            stacks[step.to].extend(lifted);
+            left -= load;
+        }
+    }
+}
+
+pub fn parse_input(input: &str) -> Result<(Stacks, Vec<Step>)> {
+    let mut stacks: Vec<Vec<char>> = Vec::new();
+    let mut steps: Vec<Step> = Vec::new();
+    let mut in_procedure = false;
+
+    for (i, line) in input.lines().enumerate() {
+        if line.is_empty() {
+            in_procedure = true;
+            continue;
+        }
+        if line.starts_with("move") {
+            // move 3 from 9 to 4
+            let step: Step = line.parse().at_line(i + 1)?;
+            steps.push(Step {
+                line: i + 1,
+                ..step
+            });
+            in_procedure = true;
+        } else if in_procedure {
+            return parse_err!("{:?} is not a procedure", line).at_line(i + 1);
+        } else if line.trim_start().starts_with(|c: char| c.is_ascii_digit()) {
+            // the numbers of the stacks, some of them may start empty
+            let count = line.split_whitespace().count();
+            if stacks.len() < count {
+                stacks.resize(count, vec![]);
+            }
+        } else {
+            for (i, c) in line.char_indices() {
+                if [' ', '[', ']'].contains(&c) || c.is_numeric() {
+                    continue;
+                } else {
+                    let index = i / 4;
+                    if stacks.len() <= index + 1 {
+                        for _ in stacks.len()..=index {
+                            stacks.push(vec![]);
+                        }
+                    }
+                    stacks[index].push(c);
+                }
+            }
+        }
+    }
+    for s in &mut stacks {
+        s.reverse();
+    }
+    for step in &steps {
+        if step.from >= stacks.len() || step.to >= stacks.len() {
+            return parse_err!("there are only {} stacks", stacks.len()).at_line(step.line);
+        }
+    }
+    Ok((Stacks(stacks), steps))
+}
+
+/// A step without its line, 0.
+impl FromStr for Step {
+    type Err = Error;
+
+    fn from_str(line: &str) -> Result<Self> {
+        let words: Vec<_> = line.split(' ').collect();
+        let ["move", count, "from", from, "to", to] = words[..] else {
+            return parse_err!("{:?} is not a procedure", line);
+        };
+        let (from, to): (usize, usize) = (from.parse()?, to.parse()?);
+        if from == 0 || to == 0 {
+            return parse_err!("stacks are numbered from 1: {:?}", line);
+        }
+        Ok(Step {
+            count: count.parse()?,
+            from: from - 1,
+            to: to - 1,
+            line: 0,
+        })
+    }
+}
+
+/// Runs the whole procedure with `crane`.
+pub fn operate(crane: &dyn Crane, mut stacks: Stacks, steps: &[Step]) -> Result<Stacks> {
+    for step in steps {
+        stacks.apply(crane, step)?;
+    }
+    Ok(stacks)
+}
+
+/// The stacks after each step of the procedure, until the end or the first step that fails.
+pub struct Replay<'a> {
+    crane: &'a dyn Crane,
+    stacks: Stacks,
+    steps: std::slice::Iter<'a, Step>,
+    failed: bool,
+}
+
+impl<'a> Replay<'a> {
+    pub fn new(crane: &'a dyn Crane, stacks: Stacks, steps: &'a [Step]) -> Self {
+        Replay {
+            crane,
+            stacks,
+            steps: steps.iter(),
+            failed: false,
+        }
+    }
+
+    /// The stacks after the last step replayed.
+    pub fn stacks(&self) -> &Stacks {
+        &self.stacks
+    }
+}
+
+impl<'a> Iterator for Replay<'a> {
+    type Item = Result<(&'a Step, Stacks)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        let step = self.steps.next()?;
+        if let Err(e) = self.stacks.apply(self.crane, step) {
+            self.failed = true;
+            return Some(Err(e));
+        }
+        Some(Ok((step, self.stacks.clone())))
+    }
+}
+
+/// Shows the stacks before the procedure and after each step, all as high as the highest.
+fn animate(crane: &dyn Crane, input: &str, show: &mut dyn FnMut(&str) -> bool) -> Result<()> {
+    let (stacks, steps) = parse_input(input)?;
+    let mut height = stacks.height();
+    for replayed in Replay::new(crane, stacks.clone(), &steps) {
+        height = height.max(replayed?.1.height());
+    }
+    if !show(&stacks.draw(height)) {
+        return Ok(());
+    }
+    for replayed in Replay::new(crane, stacks, &steps) {
+        let (step, stacks) = replayed?;
+        let frame = format!(
+            "{}\n\nmove {} from {} to {}",
+            stacks.draw(height),
+            step.count,
+            step.from + 1,
+            step.to + 1
+        );
+        if !show(&frame) {
+            break;
+        }
+    }
+    Ok(())
+}
+
+pub fn part1(stacks: Stacks, steps: &[Step]) -> Result<String> {
+    Ok(operate(&CrateMover9000, stacks, steps)?.tops())
+}
+
+pub fn part2(stacks: Stacks, steps: &[Step]) -> Result<String> {
+    Ok(operate(&CrateMover9001, stacks, steps)?.tops())
+}
+
+fn generate(rng: &mut Rng, steps: usize, knobs: &Knobs) -> Result<String> {
+    let count: usize = knobs.get::<usize>("stacks")?.max(1);
+    let crates: usize = knobs.get::<usize>("crates")?.max(1);
+    let mut stacks = vec![vec![]; count];
+    for _ in 0..crates {
+        stacks[rng.index(count)].push((b'A' + rng.below(26) as u8) as char);
+    }
+
+    let mut lines = vec![Stacks(stacks.clone()).to_string(), String::new()];
+    for _ in 0..steps.max(1) {
+        let filled: Vec<usize> = (0..count).filter(|&i| !stacks[i].is_empty()).collect();
+        let src = *rng.choose(&filled);
+        let mut dest = rng.index(count);
+        if count > 1 {
+            while dest == src {
+                dest = rng.index(count);
+            }
+        }
+        let moved = rng.between(1, stacks[src].len().min(8) as i64) as usize;
+        let at = stacks[src].len() - moved;
+        let moved = stacks[src].split_off(at);
+        stacks[dest].extend(moved.iter().rev());
+        lines.push(format!(
+            "move {} from {} to {}",
+            moved.len(),
+            src + 1,
+            dest + 1
+        ));
+    }
+    Ok(lines.join("\n"))
+}
+
+#[cfg(test)]
+const EXAMPLE: &str = "    [D]    
+[N] [C]    
+[Z] [M] [P]
+ 1   2   3 
+
+move 1 from 2 to 1
+move 3 from 1 to 3
+move 2 from 2 to 1
+move 1 from 1 to 2";
+
+#[test]
+fn example_input() {
+    let (stacks, steps) = parse_input(EXAMPLE).unwrap();
+    assert_eq!(part1(stacks.clone(), &steps).unwrap(), "CMZ");
+    assert_eq!(part2(stacks, &steps).unwrap(), "MCD");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn limited_cranes() {
+        let (stacks, steps) = parse_input(EXAMPLE).unwrap();
+        let one = LimitedCrane::new(1).unwrap();
+        assert_eq!(operate(&one, stacks.clone(), &steps).unwrap().tops(), "CMZ");
+        let three = LimitedCrane::new(3).unwrap();
+        assert_eq!(
+            operate(&three, stacks.clone(), &steps).unwrap().tops(),
+            "MCD"
+        );
+        // the 3 crates of the second step go as N D then Z
+        let two = LimitedCrane::new(2).unwrap();
+        let moved = operate(&two, stacks, &steps[..2]).unwrap();
+        assert_eq!(moved.0[2], ['P', 'N', 'D', 'Z']);
+        assert!(LimitedCrane::new(0).is_err());
+    }
+
+    #[test]
+    fn replay_and_draw() {
+        let (stacks, steps) = parse_input(EXAMPLE).unwrap();
+        assert_eq!(stacks.to_string(), EXAMPLE.split("\n\n").next().unwrap());
+        let replayed: Vec<_> = Replay::new(&CrateMover9000, stacks.clone(), &steps)
+            .map(|r| r.unwrap().1)
+            .collect();
+        assert_eq!(replayed.len(), 4);
+        assert_eq!(
+            replayed[0].to_string(),
+            "[D]        \n[N] [C]    \n[Z] [M] [P]\n 1   2   3 "
+        );
+        assert_eq!(replayed[3].tops(), "CMZ");
+        let (again, _) = parse_input(&format!("{}\n\n", replayed[1])).unwrap();
+        assert_eq!(again, replayed[1]);
+    }
+
+    #[test]
+    fn invalid_steps() {
+        let drawing = EXAMPLE.split("\n\n").next().unwrap();
+        let err = parse_input(&format!(
+            "{drawing}\n\nmove 1 from 2 to 1\nmove 1 from 4 to 1"
+        ));
+        assert!(err.unwrap_err().to_string().contains("line 7"));
+        assert!(parse_input(&format!("{drawing}\n\nmove 1 from 0 to 1")).is_err());
+        let (stacks, steps) = parse_input(&format!(
+            "{drawing}\n\nmove 2 from 1 to 3\nmove 3 from 1 to 2"
+        ))
+        .unwrap();
+        let err = part1(stacks.clone(), &steps).unwrap_err().to_string();
+        assert!(err.contains("line 7") && err.contains("it has 0"), "{err}");
+        let mut replay = Replay::new(&CrateMover9001, stacks, &steps);
+        assert!(replay.next().unwrap().is_ok());
+        assert!(replay.next().unwrap().is_err());
+        assert!(replay.next().is_none());
+        assert_eq!(replay.stacks().tops(), "DN");
+
+        // steps of another input
+        let (mut stacks, _) = parse_input(drawing).unwrap();
+        let (_, steps) = parse_input("[A] [B] [C] [D]\n\nmove 1 from 4 to 1").unwrap();
+        let err = stacks.apply(&CrateMover9000, &steps[0]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "parse error at line 3: there are only 3 stacks"
+        );
+    }
+}
@@ -0,0 +1,124 @@
+use aoc_common::{
+    parse_err, unsolvable, Answer, Generator, Knob, Knobs, Position, Result, Rng, Solution,
+};
+
+pub struct Day06;
+
+impl Solution for Day06 {
+    type Input = Vec<char>;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_input(input)
+    }
+
+    fn part1(buffer: &Self::Input) -> Result<Answer> {
+        part1(buffer).map(Answer::from)
+    }
+
+    fn part2(buffer: &Self::Input) -> Result<Answer> {
+        part2(buffer).map(Answer::from)
+    }
+
+    fn generator() -> Option<Generator> {
+        Some(Generator {
+            size: "characters",
+            default_size: 4096,
+            knobs: &[Knob {
+                name: "message",
+                about: "where the start-of-message marker ends, from 0 to 1",
+                default: "0.75",
+            }],
+            generate,
+        })
+    }
+}
+
+pub fn parse_input(input: &str) -> Result<Vec<char>> {
+    let buffer = input.trim_end();
+    if let Some((i, c)) = buffer.char_indices().find(|(_, c)| !c.is_ascii_lowercase()) {
+        return parse_err!("{c:?} is not a character of the datastream").at(1, i + 1);
+    }
+    Ok(buffer.chars().collect())
+}
+
+pub fn part1(buffer: &[char]) -> Result<usize> {
+    let length = buffer.len();
+    let mut left = 0;
+    let l = 4;
+    while left + l - 1 < length {
+        if unique(&buffer[left..left + l], l) {
+            return Ok(left + l);
+        } else {
+            left += 1;
+        }
+    }
+    unsolvable!("Can not find the first start-of-packet marker")
+}
+
+pub fn part2(buffer: &[char]) -> Result<usize> {
+    let length = buffer.len();
+    let mut left = 0;
+    let l = 14;
+    while left + l - 1 < length {
+        if unique(&buffer[left..left + l], l) {
+            return Ok(left + l);
+        } else {
+            left += 1;
+        }
+    }
+    unsolvable!("Can not find the first start-of-packet marker")
+}
+
+fn unique(chars: &[char], l: usize) -> bool {
+    use std::collections::HashSet;
+    let set: HashSet<&char> = HashSet::from_iter(chars.iter());
+    set.len() == l
+}
+
+/// `len` letters taken from `letters`, which can not have a marker longer than `letters`.
+fn filler(rng: &mut Rng, letters: &[char], len: usize) -> Vec<char> {
+    (0..len).map(|_| *rng.choose(letters)).collect()
+}
+
+fn generate(rng: &mut Rng, characters: usize, knobs: &Knobs) -> Result<String> {
+    let len = characters.max(14);
+    let message: f64 = knobs.get("message")?;
+    let message_end = ((len as f64 * message) as usize).clamp(14, len);
+
+    let mut letters: Vec<char> = ('a'..='z').collect();
+    rng.shuffle(&mut letters);
+    // everything before the start-of-message marker only has the 13 letters the marker starts
+    // with, so no earlier 14 characters are all different, and the same goes for the
+    // start-of-packet marker with 3 letters
+    let marker = &letters[..14];
+    let mut buffer = vec![];
+    let before = message_end - 14;
+    if before >= 4 {
+        let packet_end = rng.between(4, before as i64) as usize;
+        let packet = &marker[..4];
+        buffer.extend(filler(rng, &packet[..3], packet_end - 4));
+        buffer.extend(packet);
+        buffer.extend(filler(rng, &marker[..13], before - packet_end));
+    } else {
+        buffer.extend(filler(rng, &marker[..13], before));
+    }
+    buffer.extend(marker);
+    buffer.extend(filler(rng, &letters, len - message_end));
+    Ok(buffer.into_iter().collect())
+}
+
+#[test]
+fn example_input() {
+    let examples = [
+        ("mjqjpqmgbljsphdztnvjfqwrcgsmlb", 7, 19),
+        ("bvwbjplbgvbhsrlpgdmjqwftvncz", 5, 23),
+        ("nppdvjthqldpwncqszvftbrmjlhg", 6, 23),
+        ("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg", 10, 29),
+        ("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw", 11, 26),
+    ];
+    for (input, packet, message) in examples {
+        let buffer = parse_input(input).unwrap();
+        assert_eq!(part1(&buffer).unwrap(), packet);
+        assert_eq!(part2(&buffer).unwrap(), message);
+    }
+}
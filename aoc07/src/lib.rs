@@ -0,0 +1,386 @@
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+
+use aoc_common::{
+    parse_err, unsolvable, Answer, Generator, Knobs, Position, Result, Rng, Solution, Variant,
+};
+
+pub struct Day07;
+
+impl Solution for Day07 {
+    type Input = Dirs;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        build_tree(input)
+    }
+
+    fn part1(dirs: &Self::Input) -> Result<Answer> {
+        part1(dirs, 100000).map(Answer::from)
+    }
+
+    fn part2(dirs: &Self::Input) -> Result<Answer> {
+        part2(dirs).map(Answer::from)
+    }
+
+    fn variants() -> Vec<Variant> {
+        vec![
+            Variant {
+                name: "stack",
+                part: 1,
+                solve: |input| with_stack(input).map(|(part1, _)| Answer::from(part1)),
+            },
+            Variant {
+                name: "stack",
+                part: 2,
+                solve: |input| with_stack(input).map(|(_, part2)| Answer::from(part2)),
+            },
+        ]
+    }
+
+    fn generator() -> Option<Generator> {
+        Some(Generator {
+            size: "directories",
+            default_size: 180,
+            knobs: &[],
+            generate,
+        })
+    }
+}
+
+/// A file bigger than the disk can not be on it, this also keeps the sums from overflowing.
+fn parse_size(size: &str) -> Result<usize> {
+    let size = size.parse()?;
+    if size > 70000000 {
+        return parse_err!("a file of {size} does not fit on the disk");
+    }
+    Ok(size)
+}
+
+pub fn with_stack(input: &str) -> Result<(usize, usize)> {
+    let mut sizes = HashMap::new();
+    let mut sub_dirs = HashMap::new();
+    sizes.insert("/".to_string(), 0);
+    let mut pwd = vec![];
+    for (i, line) in input.lines().enumerate() {
+        if line.starts_with('$') {
+            if line.starts_with("$ cd") {
+                let dir_name = line.split(' ').next_back().unwrap();
+                if dir_name == ".." {
+                    pwd.pop();
+                } else if dir_name == "." {
+                    return parse_err!("unimplemented for path .").at_line(i + 1);
+                } else {
+                    pwd.push(dir_name);
+                }
+            } else if line.starts_with("$ ls") {
+                continue;
+            } else {
+                return parse_err!("command not found: {:?}", line).at_line(i + 1);
+            }
+        } else {
+            let path = pwd.join("/");
+            let Some((size, name)) = line.split_once(' ') else {
+                return parse_err!("not a vaild ls out put: {:?}", line).at_line(i + 1);
+            };
+            if size == "dir" {
+                sub_dirs.entry(path).or_insert(vec![]).push(name);
+            } else {
+                *sizes.entry(path).or_insert(0) += parse_size(size).at_line(i + 1)?;
+            }
+        }
+    }
+    let total_size = compute_dir_size(&sub_dirs, &mut sizes, "/");
+    if total_size > 70000000 {
+        return unsolvable!("the files take {total_size}, more than the whole disk");
+    }
+
+    // Part 1
+    let part1: usize = sizes.values().filter(|&&s| s <= 100000).sum();
+
+    // Part 2
+    let unused = 70000000 - total_size;
+    let Some(&part2) = sizes.values().filter(|&&s| unused + s >= 30000000).min() else {
+        return unsolvable!("there is not enough space even after deleting /");
+    };
+    Ok((part1, part2))
+}
+
+fn compute_dir_size(
+    sub_dirs: &HashMap<String, Vec<&str>>,
+    sizes: &mut HashMap<String, usize>,
+    path: &str,
+) -> usize {
+    if let Some(dirs) = sub_dirs.get(path) {
+        *sizes.entry(path.to_string()).or_insert(0) += dirs
+            .iter()
+            .map(|dir| compute_dir_size(sub_dirs, sizes, &format!("{path}/{dir}")))
+            .sum::<usize>()
+    }
+    // an empty directory has no size yet
+    *sizes.entry(path.to_string()).or_insert(0)
+}
+
+pub fn with_tree(input: &str) -> Result<(usize, usize)> {
+    let dirs = build_tree(input)?;
+    Ok((part1(&dirs, 100000)?, part2(&dirs)?))
+}
+
+pub fn build_tree(input: &str) -> Result<Dirs> {
+    let mut dirs = Dirs::new();
+    let mut cur_dir_index = 0;
+    for (i, line) in input.lines().enumerate() {
+        if line.starts_with('$') {
+            if line.starts_with("$ cd") {
+                let next_dir_name = line.split(' ').next_back().unwrap();
+                if next_dir_name == "/" {
+                    cur_dir_index = 0;
+                } else if next_dir_name == ".." {
+                    let p = dirs.dirs[cur_dir_index].parent;
+                    cur_dir_index = p;
+                } else if next_dir_name == "." {
+                    return parse_err!("unimplemented for path .").at_line(i + 1);
+                } else {
+                    let cur_dir = &dirs.dirs[cur_dir_index];
+                    if cur_dir.has_dir(next_dir_name) {
+                        cur_dir_index = *cur_dir.table.get(next_dir_name).unwrap();
+                    } else {
+                        return parse_err!("no such file or directory: {}", next_dir_name)
+                            .at_line(i + 1);
+                    }
+                }
+            } else if line.starts_with("$ ls") {
+                continue;
+            } else {
+                return parse_err!("command not found: {:?}", line).at_line(i + 1);
+            }
+        } else {
+            let id = dirs.dirs[cur_dir_index].id;
+            if line.starts_with("dir") {
+                if let Some(name) = line.split(' ').next_back() {
+                    dirs.add_dir(id, name.to_string());
+                } else {
+                    return parse_err!("not a vaild ls out put for sub dir: {:?}", line)
+                        .at_line(i + 1);
+                }
+            } else if let Some((size, name)) = line.split_once(' ') {
+                dirs.add_file(id, name.to_string(), parse_size(size).at_line(i + 1)?);
+            } else {
+                return parse_err!("not a vaild ls out put for file: {:?}", line).at_line(i + 1);
+            }
+        }
+    }
+    if dirs.get_size(0) > 70000000 {
+        return parse_err!(
+            "the files take {}, more than the whole disk",
+            dirs.get_size(0)
+        );
+    }
+    Ok(dirs)
+}
+
+pub fn part1(dirs: &Dirs, threshold: usize) -> Result<usize> {
+    let result: usize = dirs
+        .dirs
+        .iter()
+        .map(|d| dirs.get_size(d.id))
+        .filter(|&s| s <= threshold)
+        .sum();
+    Ok(result)
+}
+
+pub fn part2(dirs: &Dirs) -> Result<usize> {
+    let unused = 70000000 - dirs.get_size(0);
+    let result = dirs
+        .dirs
+        .iter()
+        .map(|d| dirs.get_size(d.id))
+        .filter(|&s| unused + s >= 30000000)
+        .min();
+    match result {
+        Some(result) => Ok(result),
+        None => unsolvable!("there is not enough space even after deleting /"),
+    }
+}
+
+#[derive(Debug)]
+pub struct Dirs {
+    dirs: Vec<Dir>,
+    next_index: usize,
+}
+
+impl Dirs {
+    fn new() -> Self {
+        Dirs {
+            dirs: vec![Dir::new(0, 0)],
+            next_index: 1,
+        }
+    }
+
+    fn get_size(&self, id: usize) -> usize {
+        let files_size: usize = self.dirs[id].files.values().sum();
+        let sub_dirs_size: usize = self.dirs[id]
+            .sub_dir
+            .iter()
+            .map(|&d| self.get_size(d))
+            .sum();
+        files_size + sub_dirs_size
+    }
+
+    fn add_dir(&mut self, id: usize, name: String) {
+        let dir = &mut self.dirs[id];
+        if let Entry::Vacant(e) = dir.table.entry(name) {
+            e.insert(self.next_index);
+            dir.sub_dir.push(self.next_index);
+            self.dirs.push(Dir::new(self.next_index, id));
+            self.next_index += 1;
+        }
+    }
+
+    fn add_file(&mut self, id: usize, name: String, size: usize) {
+        let dir = &mut self.dirs[id];
+        dir.files.entry(name).or_insert(size);
+    }
+}
+
+#[derive(Debug)]
+struct Dir {
+    id: usize,
+    sub_dir: Vec<usize>,
+    table: HashMap<String, usize>,
+    files: HashMap<String, usize>,
+    parent: usize,
+}
+
+impl Dir {
+    fn has_dir(&self, name: &str) -> bool {
+        self.table.contains_key(name)
+    }
+
+    fn new(id: usize, parent: usize) -> Self {
+        Dir {
+            id,
+            sub_dir: Vec::new(),
+            table: HashMap::new(),
+            files: HashMap::new(),
+            parent,
+        }
+    }
+}
+
+fn word(rng: &mut Rng, max: i64) -> String {
+    let len = rng.between(1, max);
+    (0..len)
+        .map(|_| (b'a' + rng.below(26) as u8) as char)
+        .collect()
+}
+
+/// A name that is not in `taken` yet, with an extension for some files.
+fn new_name(rng: &mut Rng, taken: &mut HashSet<String>, file: bool) -> String {
+    loop {
+        let mut name = word(rng, 8);
+        if file && rng.chance(0.5) {
+            name = format!("{name}.{}", word(rng, 3));
+        }
+        if taken.insert(name.clone()) {
+            return name;
+        }
+    }
+}
+
+fn generate(rng: &mut Rng, directories: usize, _knobs: &Knobs) -> Result<String> {
+    let count = directories.max(1);
+    let mut children = vec![vec![]; count];
+    for dir in 1..count {
+        children[rng.index(dir)].push(dir);
+    }
+    // the sizes of the files in every directory, relative to each other
+    let weights: Vec<Vec<u64>> = (0..count)
+        .map(|_| {
+            let files = rng.between(0, 4);
+            (0..files).map(|_| rng.between(1, 300) as u64).collect()
+        })
+        .collect();
+    let total_weight: u64 = weights.iter().flatten().sum::<u64>().max(1);
+    // the disk is between 60% and 97% full, so there is always something to delete
+    let used = rng.between(42_000_000, 68_000_000) as u64;
+
+    let mut lines = vec!["$ cd /".to_string()];
+    // the directories to list, with their names, in the order `cd` visits them
+    let mut stack = vec![(Some(0), None)];
+    while let Some((dir, name)) = stack.pop() {
+        let Some(dir) = dir else {
+            lines.push("$ cd ..".to_string());
+            continue;
+        };
+        if let Some(name) = name {
+            lines.push(format!("$ cd {name}"));
+        }
+        lines.push("$ ls".to_string());
+        let mut taken = HashSet::new();
+        let mut entries = vec![];
+        let mut visits = vec![];
+        for &child in &children[dir] {
+            let name = new_name(rng, &mut taken, false);
+            entries.push(format!("dir {name}"));
+            visits.push((Some(child), Some(name)));
+        }
+        for &weight in &weights[dir] {
+            let size = (weight * used / total_weight).max(1);
+            entries.push(format!("{size} {}", new_name(rng, &mut taken, true)));
+        }
+        rng.shuffle(&mut entries);
+        lines.extend(entries);
+        for visit in visits.into_iter().rev() {
+            stack.push((None, None));
+            stack.push(visit);
+        }
+    }
+    while lines.last().is_some_and(|l| l == "$ cd ..") {
+        lines.pop();
+    }
+    Ok(lines.join("\n"))
+}
+
+#[test]
+fn example_input() {
+    let input = "$ cd /
+$ ls
+dir a
+14848514 b.txt
+8504156 c.dat
+dir d
+$ cd a
+$ ls
+dir e
+29116 f
+2557 g
+62596 h.lst
+$ cd e
+$ ls
+584 i
+$ cd ..
+$ cd ..
+$ cd d
+$ ls
+4060174 j
+8033020 d.log
+5626152 d.ext
+7214296 k";
+    assert_eq!(with_stack(input).unwrap(), (95437, 24933642));
+    assert_eq!(with_tree(input).unwrap(), (95437, 24933642));
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    proptest! {
+        #[test]
+        fn stack_agrees_with_tree(directories in 1usize..300, seed: u64) {
+            let input = Day07::generator().unwrap().generate(Some(directories), seed, &[]).unwrap();
+            prop_assert_eq!(with_stack(&input).unwrap(), with_tree(&input).unwrap());
+        }
+    }
+}
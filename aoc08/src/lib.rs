@@ -0,0 +1,96 @@
+use aoc_common::{parse_err, Answer, Generator, Knobs, Result, Rng, Solution};
+use aoc_grid::{Grid, Offset, Pos, DIRECTIONS4};
+
+pub struct Day08;
+
+impl Solution for Day08 {
+    type Input = Grid<u8>;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_input(input)
+    }
+
+    fn part1(map: &Self::Input) -> Result<Answer> {
+        part1(map).map(Answer::from)
+    }
+
+    fn part2(map: &Self::Input) -> Result<Answer> {
+        part2(map).map(Answer::from)
+    }
+
+    fn generator() -> Option<Generator> {
+        Some(Generator {
+            size: "trees on a side of the square",
+            default_size: 99,
+            knobs: &[],
+            generate,
+        })
+    }
+}
+
+pub fn parse_input(input: &str) -> Result<Grid<u8>> {
+    let map = Grid::parse(input, |c| match c.to_digit(10) {
+        Some(h) => Ok(h as u8),
+        None => parse_err!("{c:?} is not a height"),
+    })?;
+    if map.width() == 0 {
+        return parse_err!("there are no trees");
+    }
+    Ok(map)
+}
+
+pub fn part1(map: &Grid<u8>) -> Result<usize> {
+    let count = map
+        .positions()
+        .filter(|&p| DIRECTIONS4.into_iter().any(|d| look(map, p, d).1))
+        .count();
+    Ok(count)
+}
+
+pub fn part2(map: &Grid<u8>) -> Result<usize> {
+    let max_score = map
+        .positions()
+        .map(|p| DIRECTIONS4.into_iter().map(|d| look(map, p, d).0).product())
+        .max()
+        .unwrap();
+    Ok(max_score)
+}
+
+/// Looks from the tree at `p` towards `direction`, returns how many trees can be seen and
+/// whether the view reaches the edge of the map.
+fn look(map: &Grid<u8>, p: Pos, direction: Offset) -> (usize, bool) {
+    let mut seen = 0;
+    let mut next = map.offset(p, direction);
+    while let Some(np) = next {
+        seen += 1;
+        if map[np] >= map[p] {
+            return (seen, false);
+        }
+        next = map.offset(np, direction);
+    }
+    (seen, true)
+}
+
+fn generate(rng: &mut Rng, side: usize, _knobs: &Knobs) -> Result<String> {
+    let side = side.max(1);
+    let rows: Vec<String> = (0..side)
+        .map(|_| {
+            (0..side)
+                .map(|_| (b'0' + rng.below(10) as u8) as char)
+                .collect()
+        })
+        .collect();
+    Ok(rows.join("\n"))
+}
+
+#[test]
+fn example_input() {
+    let input = "30373
+25512
+65332
+33549
+35390";
+    let map = parse_input(input).unwrap();
+    assert_eq!(part1(&map).unwrap(), 21);
+    assert_eq!(part2(&map).unwrap(), 8);
+}
@@ -0,0 +1,199 @@
+use std::collections::HashSet;
+use std::str::FromStr;
+
+use aoc_common::{
+    parse_err, Answer, Error, Generator, Knob, Knobs, Position, Result, Rng, Solution,
+};
+
+pub struct Day09;
+
+impl Solution for Day09 {
+    type Input = Vec<Move>;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_input(input)
+    }
+
+    fn part1(moves: &Self::Input) -> Result<Answer> {
+        part1(moves).map(Answer::from)
+    }
+
+    fn part2(moves: &Self::Input) -> Result<Answer> {
+        part2(moves).map(Answer::from)
+    }
+
+    fn generator() -> Option<Generator> {
+        Some(Generator {
+            size: "motions of the head",
+            default_size: 2000,
+            knobs: &[Knob {
+                name: "steps",
+                about: "most steps of a motion",
+                default: "19",
+            }],
+            generate,
+        })
+    }
+}
+
+type Coord = (i32, i32);
+type Step = i32;
+
+pub fn parse_input(input: &str) -> Result<Vec<Move>> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, l)| l.parse().at_line(i + 1))
+        .collect()
+}
+
+pub fn part1(moves: &[Move]) -> Result<usize> {
+    move_rope(moves, 2)
+}
+
+pub fn part2(moves: &[Move]) -> Result<usize> {
+    move_rope(moves, 10)
+}
+
+pub fn move_rope(moves: &[Move], length: usize) -> Result<usize> {
+    let mut visited = HashSet::new();
+    let mut ropes = vec![(0, 0); length];
+
+    for m in moves {
+        let steps = m.get_step();
+        let move_fn = m.move_fn();
+        for _ in 0..steps {
+            for i in 0..length - 1 {
+                if i == 0 {
+                    // only head can alway move
+                    ropes[i] = move_fn(ropes[i]);
+                }
+                ropes[i + 1] = move_tail(ropes[i], ropes[i + 1]);
+                if i + 1 == length - 1 {
+                    visited.insert(ropes[i + 1]);
+                }
+            }
+        }
+    }
+    Ok(visited.len())
+}
+
+fn move_tail(head: Coord, tail: Coord) -> Coord {
+    let d = distance(head, tail);
+    if d < 2 {
+        // touch or cover
+        tail
+    } else if head.0 - tail.0 == 0 || head.1 - tail.1 == 0 {
+        // same row or column
+        ((tail.0 + head.0) / 2, (tail.1 + head.1) / 2)
+    } else if d == 2 {
+        // diagonally
+        tail
+    } else if d > 2 {
+        // need move diagonally should be only one possible way
+        for (dx, dy) in [(1, 1), (1, -1), (-1, 1), (-1, -1)] {
+            let np = (tail.0 + dx, tail.1 + dy);
+            if distance(head, np) < d {
+                // make sure next postion will shorten the distance
+                return np;
+            }
+        }
+        unreachable!("move diagonally but no possible way");
+    } else {
+        unreachable!()
+    }
+}
+
+fn distance(head: Coord, tail: Coord) -> Step {
+    (tail.0 - head.0).abs() + (tail.1 - head.1).abs()
+}
+
+#[derive(Debug)]
+pub enum Move {
+    Left(Step),
+    Right(Step),
+    Up(Step),
+    Down(Step),
+}
+
+impl Move {
+    fn move_fn(&self) -> fn(Coord) -> Coord {
+        match self {
+            Move::Left(_) => |(x, y)| (x, y - 1),
+            Move::Right(_) => |(x, y)| (x, y + 1),
+            Move::Up(_) => |(x, y)| (x - 1, y),
+            Move::Down(_) => |(x, y)| (x + 1, y),
+        }
+    }
+
+    fn get_step(&self) -> Step {
+        match self {
+            Move::Left(s) => *s,
+            Move::Right(s) => *s,
+            Move::Up(s) => *s,
+            Move::Down(s) => *s,
+        }
+    }
+}
+
+impl FromStr for Move {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        if let Some((d, steps)) = s.split_once(' ') {
+            if let Ok(steps) = steps.parse::<Step>() {
+                match d {
+                    "L" => Ok(Move::Left(steps)),
+                    "R" => Ok(Move::Right(steps)),
+                    "U" => Ok(Move::Up(steps)),
+                    "D" => Ok(Move::Down(steps)),
+                    _ => parse_err!("This is not a valid step: {}", s),
+                }
+            } else {
+                parse_err!("This is not a valid step: {}", s)
+            }
+        } else {
+            parse_err!("This is not a valid step: {}", s)
+        }
+    }
+}
+
+fn generate(rng: &mut Rng, motions: usize, knobs: &Knobs) -> Result<String> {
+    let steps: i64 = knobs.get::<i64>("steps")?.max(1);
+    let motions: Vec<String> = (0..motions.max(1))
+        .map(|_| {
+            format!(
+                "{} {}",
+                rng.choose(&['L', 'R', 'U', 'D']),
+                rng.between(1, steps)
+            )
+        })
+        .collect();
+    Ok(motions.join("\n"))
+}
+
+#[test]
+fn example_input() {
+    let input = "R 4
+U 4
+L 3
+D 1
+R 4
+D 1
+L 5
+R 2";
+    let moves = parse_input(input).unwrap();
+    assert_eq!(part1(&moves).unwrap(), 13);
+    assert_eq!(part2(&moves).unwrap(), 1);
+
+    let input = "R 5
+U 8
+L 8
+D 3
+R 17
+D 10
+L 25
+U 20";
+    let moves = parse_input(input).unwrap();
+    assert_eq!(part2(&moves).unwrap(), 36);
+}
@@ -0,0 +1,359 @@
+use std::str::FromStr;
+
+use aoc_common::{parse_err, Answer, Error, Generator, Knobs, Position, Result, Rng, Solution};
+
+pub struct Day10;
+
+impl Solution for Day10 {
+    type Input = Cpu;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_input(input)
+    }
+
+    fn part1(cpu: &Self::Input) -> Result<Answer> {
+        part1(cpu.clone()).map(Answer::from)
+    }
+
+    fn part2(cpu: &Self::Input) -> Result<Answer> {
+        part2(cpu.clone()).map(Answer::from)
+    }
+
+    fn generator() -> Option<Generator> {
+        Some(Generator {
+            size: "instructions",
+            default_size: 140,
+            knobs: &[],
+            generate,
+        })
+    }
+}
+
+pub fn parse_input(input: &str) -> Result<Cpu> {
+    let program = input
+        .lines()
+        .enumerate()
+        .map(|(i, l)| l.parse().at_line(i + 1))
+        .collect::<Result<Vec<_>>>()?;
+    if program.is_empty() {
+        return parse_err!("the program has no instruction");
+    }
+    Ok(Cpu::new(program))
+}
+
+pub fn part1(mut cpu: Cpu) -> Result<i32> {
+    let mut strengths = 0;
+    for c in 1..300 {
+        // during cycles start with 1
+        // after cycles start with 0
+        if [20, 60, 100, 140, 180, 220].contains(&c) {
+            strengths += c * cpu.register;
+        }
+        cpu.cycle();
+    }
+    Ok(strengths)
+}
+
+pub fn part2(mut cpu: Cpu) -> Result<String> {
+    let mut crt = Crt::new();
+    for _ in 0..300 {
+        // draw a single pixel during each cycle
+        // need draw before cycle
+        crt.draw(cpu.register);
+        cpu.cycle();
+    }
+    crt.draw(cpu.register);
+    Ok(crt.show())
+}
+
+struct Crt {
+    screen: [[bool; 40]; 6],
+    cur_row: usize,
+    cur_pos: usize,
+}
+
+impl Crt {
+    fn new() -> Self {
+        Crt {
+            screen: [[false; 40]; 6],
+            cur_row: 0,
+            cur_pos: 0,
+        }
+    }
+
+    fn draw(&mut self, register: i32) {
+        if self.cur_row == 6 {
+            // all pixels are drawed
+            return;
+        }
+        if (register - self.cur_pos as i32).abs() < 2 {
+            // check current position is inside the sprite range
+            self.screen[self.cur_row][self.cur_pos] = true;
+        }
+        self.cur_pos += 1;
+        if self.cur_pos == 40 {
+            // change row and reset positon
+            self.cur_pos = 0;
+            self.cur_row += 1;
+        }
+    }
+
+    fn show(&self) -> String {
+        let mut s = String::new();
+        for i in 0..6 {
+            for j in 0..40 {
+                if self.screen[i][j] {
+                    s.push('#')
+                } else {
+                    s.push('.')
+                }
+            }
+            s.push('\n')
+        }
+        s
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Cpu {
+    register: i32,
+    program: Vec<Instruction>,
+    pc: usize,   // program counter
+    cycle: bool, // is current instruction still need one more cycle to run
+}
+
+impl Cpu {
+    fn new(instructions: Vec<Instruction>) -> Self {
+        Cpu {
+            register: 1,
+            program: instructions,
+            pc: 0,
+            cycle: false, // default fasle
+        }
+    }
+
+    fn cycle(&mut self) {
+        let instr = &self.program[self.pc % self.program.len()];
+        match instr {
+            Instruction::Addx(n) => {
+                if self.cycle {
+                    // if current instruction is addx and cycle is ture,
+                    // after this cycle the instruction will be finished
+                    self.register += n;
+                    self.cycle = false;
+                    self.pc += 1;
+                } else {
+                    // if current instruction is addx and cycle is false,
+                    // this instruction need one more cycle to run,
+                    // set cycle to true, don't increase pc
+                    self.cycle = true;
+                }
+            }
+            Instruction::Noop => self.pc += 1,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Instruction {
+    Addx(i32),
+    Noop,
+}
+
+impl FromStr for Instruction {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        if s.trim() == "noop" {
+            return Ok(Instruction::Noop);
+        }
+        if let Some((instr, op)) = s.split_once(' ') {
+            if instr.trim() == "addx" {
+                let op: i32 = op.parse()?;
+                return Ok(Instruction::Addx(op));
+            }
+        }
+        parse_err!("This is not a valid instruction: {}", s)
+    }
+}
+
+/// The CRT only has 240 pixels, and runs the program again when it is shorter.
+fn generate(rng: &mut Rng, instructions: usize, _knobs: &Knobs) -> Result<String> {
+    let mut register = 1;
+    let program: Vec<String> = (0..instructions.max(1))
+        .map(|_| {
+            if rng.chance(0.3) {
+                return "noop".to_string();
+            }
+            // the sprite stays on the screen
+            let next = rng.between(-1, 40);
+            let n = next - register;
+            register = next;
+            format!("addx {n}")
+        })
+        .collect();
+    Ok(program.join("\n"))
+}
+
+#[test]
+fn example_input() {
+    let input = "addx 15
+addx -11
+addx 6
+addx -3
+addx 5
+addx -1
+addx -8
+addx 13
+addx 4
+noop
+addx -1
+addx 5
+addx -1
+addx 5
+addx -1
+addx 5
+addx -1
+addx 5
+addx -1
+addx -35
+addx 1
+addx 24
+addx -19
+addx 1
+addx 16
+addx -11
+noop
+noop
+addx 21
+addx -15
+noop
+noop
+addx -3
+addx 9
+addx 1
+addx -3
+addx 8
+addx 1
+addx 5
+noop
+noop
+noop
+noop
+noop
+addx -36
+noop
+addx 1
+addx 7
+noop
+noop
+noop
+addx 2
+addx 6
+noop
+noop
+noop
+noop
+noop
+addx 1
+noop
+noop
+addx 7
+addx 1
+noop
+addx -13
+addx 13
+addx 7
+noop
+addx 1
+addx -33
+noop
+noop
+noop
+addx 2
+noop
+noop
+noop
+addx 8
+noop
+addx -1
+addx 2
+addx 1
+noop
+addx 17
+addx -9
+addx 1
+addx 1
+addx -3
+addx 11
+noop
+noop
+addx 1
+noop
+addx 1
+noop
+noop
+addx -13
+addx -19
+addx 1
+addx 3
+addx 26
+addx -30
+addx 12
+addx -1
+addx 3
+addx 1
+noop
+noop
+noop
+addx -9
+addx 18
+addx 1
+addx 2
+noop
+noop
+addx 9
+noop
+noop
+noop
+addx -1
+addx 2
+addx -37
+addx 1
+addx 3
+noop
+addx 15
+addx -21
+addx 22
+addx -6
+addx 1
+noop
+addx 2
+addx 1
+noop
+addx -10
+noop
+noop
+addx 20
+addx 1
+addx 2
+addx 2
+addx -6
+addx -11
+noop
+noop
+noop";
+    let cpu = parse_input(input).unwrap();
+    assert_eq!(part1(cpu.clone()).unwrap(), 13140);
+    assert_eq!(
+        part2(cpu).unwrap(),
+        "##..##..##..##..##..##..##..##..##..##..
+###...###...###...###...###...###...###.
+####....####....####....####....####....
+#####.....#####.....#####.....#####.....
+######......######......######......####
+#######.......#######.......#######.....
+"
+    );
+    assert!(parse_input("").is_err());
+}
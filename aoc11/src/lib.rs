@@ -0,0 +1,386 @@
+use std::collections::VecDeque;
+use std::str::FromStr;
+
+use aoc_common::{
+    invariant, parse_err, unsolvable, Answer, Error, Generator, Knob, Knobs, Position, Result, Rng,
+    Solution,
+};
+
+pub struct Day11;
+
+impl Solution for Day11 {
+    type Input = Vec<Monkey>;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_input(input)
+    }
+
+    fn part1(monkeys: &Self::Input) -> Result<Answer> {
+        part1(monkeys.clone()).map(Answer::from)
+    }
+
+    fn part2(monkeys: &Self::Input) -> Result<Answer> {
+        part2(monkeys.clone()).map(Answer::from)
+    }
+
+    fn generator() -> Option<Generator> {
+        Some(Generator {
+            size: "items",
+            default_size: 36,
+            knobs: &[Knob {
+                name: "monkeys",
+                about: "number of monkeys, at least 2",
+                default: "8",
+            }],
+            generate,
+        })
+    }
+}
+
+pub type Level = u64;
+
+pub fn parse_input(input: &str) -> Result<Vec<Monkey>> {
+    let mut monkeys = Vec::new();
+    let mut line = 0;
+    for s in input.split("\n\n") {
+        let monkey: Monkey = s.parse().map_err(|e| match e {
+            Error::Parse {
+                line: n,
+                column,
+                message,
+            } => Error::Parse {
+                line: line + n.max(1),
+                column,
+                message,
+            },
+            e => e,
+        })?;
+        monkeys.push(monkey);
+        line += s.lines().count() + 1;
+    }
+    if monkeys.len() < 2 {
+        return parse_err!("there are less than two monkeys");
+    }
+    for (id, m) in monkeys.iter().enumerate() {
+        if m.test.1 >= monkeys.len() || m.test.2 >= monkeys.len() {
+            return parse_err!("monkey {id} throws to a monkey that does not exist");
+        }
+        if m.test.1 == id || m.test.2 == id {
+            return parse_err!("monkey {id} throws to itself");
+        }
+    }
+    Ok(monkeys)
+}
+
+pub fn part1(mut monkeys: Vec<Monkey>) -> Result<Level> {
+    for _ in 0..20 {
+        for i in 0..monkeys.len() {
+            let l = monkeys[i].items.len();
+            for _ in 0..l {
+                let (item, m_id) = monkeys[i].part1_throw()?;
+                monkeys[m_id].items.push_back(item);
+            }
+        }
+    }
+
+    let mut result: Vec<Level> = monkeys.iter().map(|m| m.times).collect();
+    result.sort();
+    let result = result.pop().unwrap() * result.pop().unwrap();
+    Ok(result)
+}
+
+fn gcd(a: Level, b: Level) -> Level {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+pub fn part2(mut monkeys: Vec<Monkey>) -> Result<Level> {
+    // every test still gives the same answer for the levels modulo `div`
+    let div = monkeys
+        .iter()
+        .map(|m| m.test.0)
+        .fold(1, |div, test| div / gcd(div, test) * test);
+    for _ in 0..10000 {
+        for i in 0..monkeys.len() {
+            let l = monkeys[i].items.len();
+            for _ in 0..l {
+                let (item, m_id) = monkeys[i].part2_throw(div)?;
+                monkeys[m_id].items.push_back(item);
+            }
+        }
+    }
+
+    let mut result: Vec<Level> = monkeys.iter().map(|m| m.times).collect();
+    result.sort();
+    let result = result.pop().unwrap() * result.pop().unwrap();
+    Ok(result)
+}
+
+#[derive(Debug, Clone)]
+enum Operation {
+    Old,
+    Num(Level),
+    Add,
+    Multi,
+}
+
+#[derive(Debug, Clone)]
+pub struct Monkey {
+    items: VecDeque<Level>,
+    operation: (Operation, Operation, Operation),
+    test: (Level, usize, usize),
+    times: Level,
+}
+
+impl Monkey {
+    fn part1_throw(&mut self) -> Result<(Level, usize)> {
+        if let Some(item) = self.items.pop_front() {
+            self.times += 1;
+            let op1 = match self.operation.0 {
+                Operation::Old => item,
+                Operation::Num(n) => n,
+                _ => return invariant!("This is not a valid operation"),
+            };
+            let op2 = match self.operation.1 {
+                Operation::Old => item,
+                Operation::Num(n) => n,
+                _ => return invariant!("This is not a valid operation"),
+            };
+            let new = match self.operation.2 {
+                Operation::Add => op1.checked_add(op2),
+                Operation::Multi => op1.checked_mul(op2),
+                _ => return invariant!("This is not a valid operation"),
+            };
+            let Some(new) = new else {
+                return unsolvable!("a worry level does not fit in 64 bits");
+            };
+            let new = new / 3;
+            return if new % self.test.0 == 0 {
+                Ok((new, self.test.1))
+            } else {
+                Ok((new, self.test.2))
+            };
+        }
+
+        invariant!("can not throw")
+    }
+
+    fn part2_throw(&mut self, div: Level) -> Result<(Level, usize)> {
+        if let Some(item) = self.items.pop_front() {
+            self.times += 1;
+            let op1 = match self.operation.0 {
+                Operation::Old => item,
+                Operation::Num(n) => n,
+                _ => return invariant!("This is not a valid operation"),
+            };
+            let op2 = match self.operation.1 {
+                Operation::Old => item,
+                Operation::Num(n) => n,
+                _ => return invariant!("This is not a valid operation"),
+            };
+            let new = match self.operation.2 {
+                Operation::Add => (op1 + op2) % div,
+                Operation::Multi => op1 % div * op2 % div % div,
+                _ => return invariant!("This is not a valid operation"),
+            };
+            return if new % self.test.0 == 0 {
+                Ok((new, self.test.1))
+            } else {
+                Ok((new, self.test.2))
+            };
+        }
+
+        invariant!("can not throw")
+    }
+}
+
+impl FromStr for Operation {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Ok(match s {
+            "old" => Operation::Old,
+            "*" => Operation::Multi,
+            "+" => Operation::Add,
+            _ => Operation::Num(s.parse()?),
+        })
+    }
+}
+
+fn field<'a>(line: &'a str, prefix: &str, n: usize) -> Result<&'a str> {
+    match line.strip_prefix(prefix) {
+        Some(field) => Ok(field),
+        None => parse_err!("expect {:?}", prefix).at_line(n),
+    }
+}
+
+/// Line numbers of the errors are counted from the first line of the monkey.
+impl FromStr for Monkey {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let lines: Vec<&str> = s.lines().map(|s| s.trim()).collect();
+        let [header, items, operation, test, if_true, if_false] = lines[..] else {
+            return parse_err!("This is not a monkey: {}", s);
+        };
+        if !header.starts_with("Monkey") {
+            return parse_err!("This is not a monkey: {}", s).at_line(1);
+        }
+        let items = field(items, "Starting items: ", 2)?
+            .split(", ")
+            .filter(|i| !i.is_empty())
+            .map(|i| i.parse().at_line(2))
+            .collect::<Result<VecDeque<Level>>>()?;
+        let op: Vec<&str> = field(operation, "Operation: new = ", 3)?
+            .split(' ')
+            .collect();
+        let [op1, op, op2] = op[..] else {
+            return parse_err!("This is not a valid operation").at_line(3);
+        };
+        let (op1, op2) = (op1.parse().at_line(3)?, op2.parse().at_line(3)?);
+        let op = match op {
+            "*" => Operation::Multi,
+            "+" => Operation::Add,
+            _ => return parse_err!("This is not a valid operation").at_line(3),
+        };
+        if matches!(op1, Operation::Add | Operation::Multi)
+            || matches!(op2, Operation::Add | Operation::Multi)
+        {
+            return parse_err!("This is not a valid operation").at_line(3);
+        }
+        let div = field(test, "Test: divisible by ", 4)?
+            .parse::<Level>()
+            .at_line(4)?;
+        if div == 0 {
+            return parse_err!("can not divide by zero").at_line(4);
+        }
+        let m1 = field(if_true, "If true: throw to monkey ", 5)?
+            .parse()
+            .at_line(5)?;
+        let m2 = field(if_false, "If false: throw to monkey ", 6)?
+            .parse()
+            .at_line(6)?;
+        Ok(Monkey {
+            items,
+            operation: (op1, op2, op),
+            test: (div, m1, m2),
+            times: 0,
+        })
+    }
+}
+
+const PRIMES: [Level; 9] = [2, 3, 5, 7, 11, 13, 17, 19, 23];
+
+fn monkey(
+    rng: &mut Rng,
+    id: usize,
+    count: usize,
+    items: &[Level],
+    test: Level,
+    square: bool,
+) -> String {
+    let operation = if square {
+        "old * old".to_string()
+    } else if rng.chance(0.5) {
+        format!("old * {}", rng.between(2, 19))
+    } else {
+        format!("old + {}", rng.between(1, 8))
+    };
+    let mut other = || loop {
+        let to = rng.index(count);
+        if to != id {
+            return to;
+        }
+    };
+    let if_true = other();
+    let mut if_false = other();
+    while count > 2 && if_false == if_true {
+        if_false = other();
+    }
+    let items: Vec<_> = items.iter().map(|i| i.to_string()).collect();
+    format!(
+        "Monkey {id}:
+  Starting items: {}
+  Operation: new = {operation}
+  Test: divisible by {test}
+    If true: throw to monkey {if_true}
+    If false: throw to monkey {if_false}",
+        items.join(", ")
+    )
+}
+
+/// Like in the real input only one monkey squares the levels, but they can still overflow in
+/// part 1, then the monkeys are made again. With only 2 monkeys none squares the levels, the
+/// items would go back to it every other throw.
+fn generate(rng: &mut Rng, items: usize, knobs: &Knobs) -> Result<String> {
+    let count = knobs.get::<usize>("monkeys")?.max(2);
+    let items = items.max(count);
+    for _ in 0..1000 {
+        // every monkey has an item to start with
+        let mut held: Vec<Vec<Level>> = (0..count).map(|_| vec![]).collect();
+        for i in 0..items {
+            let id = if i < count { i } else { rng.index(count) };
+            held[id].push(rng.between(50, 99) as Level);
+        }
+        let mut tests = PRIMES.to_vec();
+        rng.shuffle(&mut tests);
+        let squares = (count > 2).then(|| rng.index(count));
+        let monkeys: Vec<String> = (0..count)
+            .map(|id| {
+                let test = tests
+                    .get(id)
+                    .copied()
+                    .unwrap_or_else(|| *rng.choose(&PRIMES));
+                monkey(rng, id, count, &held[id], test, squares == Some(id))
+            })
+            .collect();
+        let input = monkeys.join("\n\n");
+        if part1(parse_input(&input)?).is_ok() {
+            return Ok(input);
+        }
+    }
+    unsolvable!("the worry levels of part 1 overflow with every try")
+}
+
+#[cfg(test)]
+mod test {
+
+    #[test]
+    fn example_input() {
+        use crate::{parse_input, part1, part2};
+
+        let input = "Monkey 0:
+  Starting items: 79, 98
+  Operation: new = old * 19
+  Test: divisible by 23
+    If true: throw to monkey 2
+    If false: throw to monkey 3
+
+Monkey 1:
+  Starting items: 54, 65, 75, 74
+  Operation: new = old + 6
+  Test: divisible by 19
+    If true: throw to monkey 2
+    If false: throw to monkey 0
+
+Monkey 2:
+  Starting items: 79, 60, 97
+  Operation: new = old * old
+  Test: divisible by 13
+    If true: throw to monkey 1
+    If false: throw to monkey 3
+
+Monkey 3:
+  Starting items: 74
+  Operation: new = old + 3
+  Test: divisible by 17
+    If true: throw to monkey 0
+    If false: throw to monkey 1";
+        let monkeys = parse_input(input).unwrap();
+        assert_eq!(part1(monkeys.clone()).unwrap(), 10605);
+        assert_eq!(part2(monkeys).unwrap(), 2713310158);
+    }
+}
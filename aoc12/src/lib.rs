@@ -0,0 +1,241 @@
+use aoc_common::{parse_err, unsolvable, Answer, Generator, Knobs, Result, Rng, Solution, Variant};
+use aoc_grid::{Grid, Pos};
+use aoc_search::Path;
+
+pub struct Day12;
+
+impl Solution for Day12 {
+    type Input = Grid<char>;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_input(input)
+    }
+
+    fn part1(grid: &Self::Input) -> Result<Answer> {
+        part1(grid).map(Answer::from)
+    }
+
+    fn part2(grid: &Self::Input) -> Result<Answer> {
+        part2_with_reverse_bfs(grid).map(Answer::from)
+    }
+
+    fn variants() -> Vec<Variant> {
+        vec![Variant {
+            name: "bfs from every start",
+            part: 2,
+            solve: |input| part2(&parse_input(input)?).map(Answer::from),
+        }]
+    }
+
+    fn generator() -> Option<Generator> {
+        Some(Generator {
+            size: "columns, with a row for every four",
+            default_size: 160,
+            knobs: &[],
+            generate,
+        })
+    }
+}
+
+pub fn parse_input(input: &str) -> Result<Grid<char>> {
+    let grid = Grid::parse(input, |c| {
+        if c.is_ascii_lowercase() || c == 'S' || c == 'E' {
+            Ok(c)
+        } else {
+            parse_err!("{c:?} is not an elevation")
+        }
+    })?;
+    if grid.width() == 0 {
+        return parse_err!("the heightmap is empty");
+    }
+    for c in ['S', 'E'] {
+        let count = grid.iter().filter(|(_, &h)| h == c).count();
+        if count != 1 {
+            return parse_err!("there should be one {c} in the heightmap, found {count}");
+        }
+    }
+    Ok(grid)
+}
+
+pub fn part1(grid: &Grid<char>) -> Result<usize> {
+    // bfs
+    let Some(start) = grid.position(|&c| c == 'S') else {
+        return unsolvable!("There is no start in the heightmap");
+    };
+    let result = bfs(grid, start)?.cost;
+    Ok(result)
+}
+
+pub fn part2(grid: &Grid<char>) -> Result<usize> {
+    // bfs
+    let mut result = usize::MAX;
+    for (p, &c) in grid.iter() {
+        if c == 'S' || c == 'a' {
+            result = result.min(bfs(grid, p).map_or(usize::MAX, |path| path.cost));
+        }
+    }
+    if result == usize::MAX {
+        return unsolvable!("There is not valid router found");
+    }
+    Ok(result)
+}
+
+pub fn part2_with_reverse_bfs(grid: &Grid<char>) -> Result<usize> {
+    let Some(end) = grid.position(|&c| c == 'E') else {
+        return unsolvable!("There is no best signal in the heightmap");
+    };
+    let result = reverse_bfs(grid, end)?.cost;
+    Ok(result)
+}
+
+/// The fewest steps from `start` to the best signal.
+pub fn bfs(grid: &Grid<char>, start: Pos) -> Result<Path<Pos>> {
+    let path = aoc_search::bfs(
+        [start],
+        |&p| {
+            grid.neighbors4(p)
+                .filter(move |&n| reachable(grid[p], grid[n]))
+        },
+        |&p| is_dest(grid[p]),
+    );
+    match path {
+        Some(path) => Ok(path),
+        None => unsolvable!("There is not valid router found"),
+    }
+}
+
+/// The fewest steps from the lowest square closest to `end` up to it, the path is walked
+/// backwards.
+pub fn reverse_bfs(grid: &Grid<char>, end: Pos) -> Result<Path<Pos>> {
+    // from E to S
+    let path = aoc_search::bfs(
+        [end],
+        |&p| {
+            grid.neighbors4(p)
+                .filter(move |&n| reachable(grid[n], grid[p]))
+        },
+        |&p| grid[p] == 'S' || grid[p] == 'a',
+    );
+    match path {
+        Some(path) => Ok(path),
+        None => unsolvable!("There is not valid router found"),
+    }
+}
+
+fn is_dest(c: char) -> bool {
+    c == 'E'
+}
+
+fn reachable(c: char, n: char) -> bool {
+    if c == 'S' {
+        n == 'a' || n == 'b'
+    } else if n == 'E' {
+        c == 'z' || c == 'y'
+    } else if n == 'S' {
+        false
+    } else {
+        (n as u8).saturating_sub(c as u8) <= 1
+    }
+}
+
+/// A hill rising from S to E, with pits in it, made again until E can be reached.
+fn generate(rng: &mut Rng, columns: usize, _knobs: &Knobs) -> Result<String> {
+    // the path must have 25 steps to climb from a to z
+    let width = columns.max(26);
+    let height = (width / 4).max(1);
+    let distance = |a: Pos, b: Pos| a.0.abs_diff(b.0) + a.1.abs_diff(b.1);
+    for _ in 0..1000 {
+        let start = (rng.index(height), rng.index(width));
+        let end = (rng.index(height), rng.index(width));
+        let climb = distance(start, end);
+        if climb < 25 {
+            continue;
+        }
+        let mut grid = Grid::new(height, width, 'a');
+        for p in grid.positions() {
+            // neighbors are at most one higher than each other, going away from E
+            let level = 25usize.saturating_sub(distance(p, end) * 25 / climb);
+            let level = if rng.chance(0.15) {
+                rng.index(level + 1)
+            } else {
+                level
+            };
+            grid[p] = (b'a' + level as u8) as char;
+        }
+        grid[start] = 'S';
+        grid[end] = 'E';
+        if part1(&grid).is_ok() {
+            return Ok(grid.render(|&c| c).trim_end().to_string());
+        }
+    }
+    unsolvable!("could not make a heightmap with a way to E")
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn test_reachable() {
+        assert!(reachable('a', 'b'));
+        assert!(!reachable('a', 'd'));
+        assert!(reachable('f', 'a'));
+        assert!(!reachable('v', 'E'));
+    }
+
+    #[test]
+    fn test_part1() {
+        let input = "Sabqponm
+        abcryxxl
+        accszExk
+        acctuvwj
+        abdefghi";
+        let input: String = input.lines().map(|l| format!("{}\n", l.trim())).collect();
+        let grid = crate::parse_input(&input).unwrap();
+        let r = part1(&grid).unwrap();
+        assert_eq!(r, 31);
+
+        let path = crate::bfs(&grid, (0, 0)).unwrap();
+        assert_eq!(path.nodes.len(), 32);
+        assert_eq!(path.nodes.last(), Some(&(2, 5)));
+    }
+
+    #[test]
+    fn test_part2() {
+        let input = "Sabqponm
+        abcryxxl
+        accszExk
+        acctuvwj
+        abdefghi";
+        let input: String = input.lines().map(|l| format!("{}\n", l.trim())).collect();
+        let grid = crate::parse_input(&input).unwrap();
+        let r = part2(&grid).unwrap();
+        assert_eq!(r, 29);
+    }
+
+    #[test]
+    fn test_part2_reverse_bfs() {
+        let input = "Sabqponm
+        abcryxxl
+        accszExk
+        acctuvwj
+        abdefghi";
+        let input: String = input.lines().map(|l| format!("{}\n", l.trim())).collect();
+        let grid = crate::parse_input(&input).unwrap();
+        let r = part2_with_reverse_bfs(&grid).unwrap();
+        assert_eq!(r, 29);
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(64))]
+
+        #[test]
+        fn reverse_bfs_agrees_with_bfs_from_every_start(columns in 26usize..60, seed: u64) {
+            let input = Day12::generator().unwrap().generate(Some(columns), seed, &[]).unwrap();
+            let grid = parse_input(&input).unwrap();
+            prop_assert_eq!(part2(&grid).unwrap(), part2_with_reverse_bfs(&grid).unwrap());
+        }
+    }
+}
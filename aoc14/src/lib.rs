@@ -0,0 +1,401 @@
+use std::str::FromStr;
+
+use aoc_common::{
+    parse_err, Animation, Answer, Error, Generator, Knobs, Position, Result, Rng, Solution,
+};
+use aoc_grid::{Grid, Pos};
+
+pub struct Day14;
+
+impl Solution for Day14 {
+    type Input = Cave;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_input(input)
+    }
+
+    fn part1(cave: &Self::Input) -> Result<Answer> {
+        part1(cave.clone()).map(Answer::from)
+    }
+
+    fn part2(cave: &Self::Input) -> Result<Answer> {
+        part2(cave.clone()).map(Answer::from)
+    }
+
+    fn animations() -> Vec<Animation> {
+        vec![
+            Animation {
+                name: "falling sand",
+                part: 1,
+                animate: |input, screen| {
+                    let mut cave = parse_input(input)?;
+                    cave.sand_fall_part1(|cave| screen.show(&cave.draw_cave(false)));
+                    Ok(())
+                },
+            },
+            Animation {
+                name: "falling sand on the floor",
+                part: 2,
+                animate: |input, screen| {
+                    let mut cave = parse_input(input)?;
+                    cave.sand_fall_part2(|cave| screen.show(&cave.draw_cave(false)));
+                    Ok(())
+                },
+            },
+        ]
+    }
+
+    fn generator() -> Option<Generator> {
+        Some(Generator {
+            size: "paths of rock",
+            default_size: 150,
+            knobs: &[],
+            generate,
+        })
+    }
+}
+
+pub fn parse_input(input: &str) -> Result<Cave> {
+    let paths = input
+        .lines()
+        .enumerate()
+        .map(|(i, l)| {
+            l.trim()
+                .split(" -> ")
+                .map(|c| c.parse().at_line(i + 1))
+                .collect()
+        })
+        .collect::<Result<Vec<Vec<Coord>>>>()?;
+    for (i, path) in paths.iter().enumerate() {
+        for c in path {
+            if !(1..=MAX_X).contains(&c.x) || c.y > MAX_Y {
+                return parse_err!("({}, {}) is too far from the source of the sand", c.x, c.y)
+                    .at_line(i + 1);
+            }
+        }
+        if path
+            .windows(2)
+            .any(|r| r[0].x != r[1].x && r[0].y != r[1].y)
+        {
+            return parse_err!("rocks can not be placed diagonally").at_line(i + 1);
+        }
+    }
+    Ok(Cave::new(&paths))
+}
+
+/// The cave is a grid around the source of the sand, the floor of part 2 has to fit in it.
+const MAX_X: usize = 1000;
+const MAX_Y: usize = 497;
+
+pub fn part1(mut cave: Cave) -> Result<usize> {
+    let result = cave.sand_fall_part1(|_| true);
+    Ok(result)
+}
+
+pub fn part2(mut cave: Cave) -> Result<usize> {
+    let result = cave.sand_fall_part2(|_| true);
+    Ok(result)
+}
+
+#[derive(Clone)]
+pub struct Cave {
+    // grid: HashMap<Coord, Material>,
+    /// Rows are `y`, columns are `x - min_x`.
+    grid: Grid<Material>,
+    // range_x: (usize, usize),
+    // range_y: (usize, usize),
+    sand_src: Coord,
+    max_y: usize,
+    min_x: usize,
+    max_x: usize,
+}
+
+impl Cave {
+    fn new(paths: &[Vec<Coord>]) -> Self {
+        use Material::*;
+
+        let mut max_y = 0;
+        let mut min_x = usize::MAX;
+        let mut max_x = 0;
+        for path in paths {
+            for coord in path {
+                max_y = max_y.max(coord.y);
+                min_x = min_x.min(coord.x);
+                max_x = max_x.max(coord.x);
+            }
+        }
+        max_y += 2;
+        min_x = min_x.saturating_sub(2);
+        max_x += 2;
+        let min_x = min_x.min(500 - max_y);
+        let max_x = max_x.max(500 + max_y);
+        let mut grid = Grid::new(max_y + 1, max_x - min_x + 1, Air);
+
+        for path in paths {
+            for route in path.windows(2) {
+                let (start, end) = route[0].range(&route[1]);
+                for x in start.x..=end.x {
+                    for y in start.y..=end.y {
+                        grid[(y, x - min_x)] = Rock
+                    }
+                }
+            }
+        }
+        Cave {
+            grid,
+            sand_src: Coord::new(500, 0),
+            max_y,
+            min_x,
+            max_x,
+        }
+    }
+
+    // fn update_cave_size(&mut self, c: Coord) {
+    //     self.range_x.0 = self.range_x.0.min(c.x);
+    //     self.range_x.1 = self.range_x.1.max(c.x);
+    //     self.range_y.0 = self.range_y.0.min(c.y);
+    //     self.range_y.1 = self.range_y.1.max(c.y);
+    // }
+
+    /// `on_rest` is called every time a unit of sand comes to rest, the sand stops falling when
+    /// it returns `false`.
+    fn sand_fall_part1(&mut self, mut on_rest: impl FnMut(&Cave) -> bool) -> usize {
+        let mut count = 0;
+        let mut cur = self.sand_src;
+        while !self.in_abyss(&cur) {
+            let next = cur.down();
+            if !self.is_blocked(&next) {
+                cur = next;
+                continue;
+            }
+            let next = cur.left();
+            if !self.is_blocked(&next) {
+                cur = next;
+                continue;
+            }
+            let next = cur.right();
+            if !self.is_blocked(&next) {
+                cur = next;
+                continue;
+            }
+            self.sand_rest(cur);
+            count += 1;
+            if !on_rest(self) || cur == self.sand_src {
+                break;
+            }
+            cur = self.sand_src;
+        }
+        count
+    }
+
+    fn is_blocked(&self, c: &Coord) -> bool {
+        if c.y == self.max_y {
+            return true;
+        }
+        match self.grid[self.pos(c)] {
+            Material::Air => false,
+            Material::Rock | Material::Sand => true,
+        }
+    }
+
+    fn sand_rest(&mut self, c: Coord) {
+        // self.update_cave_size(c);
+        let pos = self.pos(&c);
+        self.grid[pos] = Material::Sand
+    }
+
+    fn pos(&self, c: &Coord) -> Pos {
+        (c.y, c.x - self.min_x)
+    }
+
+    fn in_abyss(&self, c: &Coord) -> bool {
+        c.y > self.max_y - 3 || c.x < self.min_x || c.x >= self.max_x
+    }
+
+    /// Like [`Cave::sand_fall_part1`], until the source of the sand is blocked.
+    fn sand_fall_part2(&mut self, mut on_rest: impl FnMut(&Cave) -> bool) -> usize {
+        let mut count = 0;
+        let mut cur = self.sand_src;
+        loop {
+            let next = cur.down();
+            if !self.is_blocked(&next) {
+                cur = next;
+                continue;
+            }
+            let next = cur.left();
+            if !self.is_blocked(&next) {
+                cur = next;
+                continue;
+            }
+            let next = cur.right();
+            if !self.is_blocked(&next) {
+                cur = next;
+                continue;
+            }
+            self.sand_rest(cur);
+            count += 1;
+            if !on_rest(self) {
+                break;
+            }
+            if cur != self.sand_src {
+                cur = self.sand_src;
+            } else {
+                break;
+            }
+        }
+        count
+    }
+
+    /// The `legend` gives the coordinates of the corners and of the source of the sand.
+    fn draw_cave(&self, legend: bool) -> String {
+        use Material::*;
+
+        let mut map = String::new();
+        if legend {
+            map.push_str(&format!(
+                "x: {} -> {} (left -> right)\n",
+                self.min_x, self.max_x
+            ));
+            map.push_str(&format!("y: {} -> {} (top -> bottom)\n", 0, self.max_y));
+            map.push_str(&format!(
+                "sand sorce: ({}, {})\n",
+                self.sand_src.x, self.sand_src.y
+            ));
+        }
+        let src = self.pos(&self.sand_src);
+        for ((y, x), m) in self.grid.iter() {
+            if (y, x) == src {
+                map.push('+');
+            } else if y == self.max_y {
+                map.push('#');
+            } else {
+                match m {
+                    Air => map.push('.'),
+                    Rock => map.push('#'),
+                    Sand => map.push('o'),
+                }
+            }
+            if x + 1 == self.grid.width() {
+                map.push('\n');
+            }
+        }
+        map
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Material {
+    Air,
+    Rock,
+    Sand,
+}
+
+/// The rocks are in the triangle the sand can reach, deeper with more of them.
+fn generate(rng: &mut Rng, paths: usize, _knobs: &Knobs) -> Result<String> {
+    let depth = (paths + 20).min(MAX_Y) as i64;
+    let paths: Vec<String> = (0..paths.max(1))
+        .map(|_| {
+            // as many rocks in every part of the triangle, which is wider at the bottom
+            let u = rng.below(1 << 20) as f64 / (1 << 20) as f64;
+            let y = 10 + ((depth - 10) as f64 * u.sqrt()) as i64;
+            let mut c = (rng.between(500 - y, 500 + y), y);
+            let mut path = vec![c];
+            for i in 0..rng.between(1, 6) {
+                let step = rng.between(-8, 8);
+                if i % 2 == 0 {
+                    c.0 = (c.0 + step).clamp(1, MAX_X as i64);
+                } else {
+                    c.1 = (c.1 + step).clamp(10, depth);
+                }
+                path.push(c);
+            }
+            let path: Vec<_> = path.iter().map(|(x, y)| format!("{x},{y}")).collect();
+            path.join(" -> ")
+        })
+        .collect();
+    Ok(paths.join("\n"))
+}
+
+#[derive(Debug, Clone, Copy, Hash, Eq, PartialEq, PartialOrd, Ord)]
+struct Coord {
+    x: usize,
+    y: usize,
+}
+
+impl Coord {
+    fn new(x: usize, y: usize) -> Self {
+        Self { x, y }
+    }
+
+    fn range(&self, other: &Coord) -> (Coord, Coord) {
+        let (start, end) = if self < other {
+            (self, other)
+        } else {
+            (other, self)
+        };
+        (*start, *end)
+    }
+
+    fn down(self) -> Self {
+        Coord {
+            y: self.y + 1,
+            x: self.x,
+        }
+    }
+
+    fn left(self) -> Self {
+        Coord {
+            y: self.y + 1,
+            x: self.x - 1,
+        }
+    }
+
+    fn right(self) -> Self {
+        Coord {
+            y: self.y + 1,
+            x: self.x + 1,
+        }
+    }
+}
+
+impl FromStr for Coord {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        if let Some((x, y)) = s.split_once(',') {
+            return Ok(Coord {
+                x: x.parse()?,
+                y: y.parse()?,
+            });
+        }
+        parse_err!("not a valid coordinate: {}", s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_example() {
+        use crate::{Cave, Coord};
+
+        let input = "498,4 -> 498,6 -> 496,6
+        503,4 -> 502,4 -> 502,9 -> 494,9";
+        let paths: Vec<Vec<Coord>> = input
+            .lines()
+            .map(|l| l.trim().split(" -> ").map(|c| c.parse().unwrap()).collect())
+            .collect();
+        let cave = Cave::new(&paths);
+        println!("{}", cave.draw_cave(true));
+        assert_eq!(paths.len(), 2);
+        assert_eq!(paths[0].len(), 3);
+        assert_eq!(paths[1].len(), 4);
+        let mut part1_cave = cave.clone();
+        assert_eq!(24, part1_cave.sand_fall_part1(|_| true));
+        println!("{}", part1_cave.draw_cave(true));
+
+        let mut part2_cave = cave.clone();
+        let r = part2_cave.sand_fall_part2(|_| true);
+        println!("{}", part2_cave.draw_cave(true));
+        assert_eq!(93, r);
+    }
+}
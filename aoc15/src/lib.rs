@@ -0,0 +1,382 @@
+use lazy_static::lazy_static;
+use std::collections::HashSet;
+
+use aoc_common::{
+    parse_err, unsolvable, Answer, Generator, Knobs, Position, Result, Rng, Solution, Variant,
+};
+use aoc_interval::IntervalSet;
+use regex::Regex;
+
+pub struct Day15;
+
+impl Solution for Day15 {
+    type Input = Vec<Sensor>;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_input(input)
+    }
+
+    fn part1(sensors: &Self::Input) -> Result<Answer> {
+        part1_with_interval(sensors, 2000000).map(Answer::from)
+    }
+
+    fn part2(sensors: &Self::Input) -> Result<Answer> {
+        part2_with_interval(sensors, 4000000).map(Answer::from)
+    }
+
+    fn variants() -> Vec<Variant> {
+        vec![
+            Variant {
+                name: "scan",
+                part: 1,
+                solve: |input| part1(&parse_input(input)?, 2000000).map(Answer::from),
+            },
+            Variant {
+                name: "scan",
+                part: 2,
+                solve: |input| part2(&parse_input(input)?, 4000000).map(Answer::from),
+            },
+        ]
+    }
+
+    fn generator() -> Option<Generator> {
+        Some(Generator {
+            size: "sensors",
+            default_size: 30,
+            knobs: &[],
+            generate,
+        })
+    }
+}
+
+pub type CoordType = i64;
+type Coord = (CoordType, CoordType);
+
+pub fn parse_input(input: &str) -> Result<Vec<Sensor>> {
+    let mut sensors = parse_report(input)?;
+    if sensors.is_empty() {
+        return parse_err!("there are no sensors");
+    }
+    sensors.sort_by_key(|s| s.min_x());
+    Ok(sensors)
+}
+
+pub fn part1(sensors: &[Sensor], y: CoordType) -> Result<CoordType> {
+    let min_x = sensors.iter().map(|s| s.min_x()).min().unwrap();
+    let max_x = sensors.iter().map(|s| s.max_x()).max().unwrap();
+    let result = (min_x..=max_x)
+        .filter(|&x| {
+            // a position isn't a beacon for a sensor then it must not be a beacon
+            sensors.iter().any(|s| !s.could_be_beacon((x, y)))
+        })
+        .count();
+    Ok(result as CoordType)
+}
+
+pub fn part2(sensors: &[Sensor], max: CoordType) -> Result<CoordType> {
+    let mut result = 0;
+    let mut y = -1;
+    while y <= max && result == 0 {
+        y += 1;
+        let mut x = 0;
+        let mut flag;
+        while x <= max && result == 0 {
+            flag = false;
+            for s in sensors {
+                // a known beacon is not the distress beacon either
+                if s.reaches((x, y)) {
+                    flag = true;
+                    x = s.furthest_horizontal((x, y)).0 + 1;
+                    break;
+                }
+            }
+            if !flag {
+                result = x * 4000000 + y;
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+pub fn part1_with_interval(sensors: &[Sensor], y: CoordType) -> Result<CoordType> {
+    let result = intervals_at(sensors, y).0.coverage()
+        - sensors
+            .iter()
+            .map(|s| s.beacon)
+            .filter(|b| b.1 == y)
+            .collect::<HashSet<_>>()
+            .len() as CoordType;
+    Ok(result)
+}
+
+/// The rows are cut in this many bands, searched at the same time with the `parallel` feature.
+/// The bands do not depend on the number of threads, so neither does the answer.
+#[cfg(feature = "parallel")]
+const BANDS: CoordType = 64;
+
+pub fn part2_with_interval(sensors: &[Sensor], max: CoordType) -> Result<CoordType> {
+    #[cfg(not(feature = "parallel"))]
+    let result = first_gap(sensors, max, 0, max);
+    #[cfg(feature = "parallel")]
+    let result = {
+        use rayon::prelude::*;
+        let band = (max + BANDS) / BANDS;
+        (0..BANDS)
+            .into_par_iter()
+            .find_map_first(|i| first_gap(sensors, max, i * band, ((i + 1) * band - 1).min(max)))
+    };
+    match result {
+        Some(result) => Ok(result),
+        None => unsolvable!("there is no gap between the sensors in the rows from 0 to {max}"),
+    }
+}
+
+/// The tuning frequency of the first gap between the rows `from` and `to`, in the columns from 0
+/// to `max`.
+fn first_gap(
+    sensors: &[Sensor],
+    max: CoordType,
+    from: CoordType,
+    to: CoordType,
+) -> Option<CoordType> {
+    let mut y = from;
+    while y <= to {
+        let (intervals, temp) = intervals_at(sensors, y);
+        let gaps = intervals.gaps_within((0, max));
+        if let Some(&(x, _)) = gaps.intervals().first() {
+            return Some(y + 4000000 * x);
+        }
+        // the columns from 0 to `max` are in one interval, which shrinks by at most 1 on each side
+        // from one row to the next
+        let margin = match intervals.intervals().iter().find(|&&(_, e)| e >= 0) {
+            Some(&(s, e)) => (1 - s).min(e - max + 1).saturating_mul(2),
+            None => 0,
+        };
+        // the overlap is 0 when two sensors only touch, a gap may open on the next row already
+        let overlap = temp.min(margin);
+        y = y.saturating_add((overlap.saturating_add(1) / 2).max(1));
+    }
+    None
+}
+
+/// The columns of the row `y` reached by the sensors, and the smallest overlap between two
+/// sensors that left no gap, which shrinks by at most 2 from one row to the next.
+fn intervals_at(sensors: &[Sensor], y: CoordType) -> (IntervalSet, CoordType) {
+    let mut raw: Vec<_> = sensors.iter().filter_map(|s| s.interval_at(y)).collect();
+    raw.sort();
+    let mut intervals = IntervalSet::new();
+    let mut min_overlap = CoordType::MAX;
+    for &interval in &raw {
+        if let Some(&(_, end)) = intervals.intervals().last() {
+            min_overlap = min_overlap.min((end - interval.0 + 1).max(0));
+        }
+        intervals.insert(interval);
+    }
+    (intervals, min_overlap)
+}
+
+#[derive(Debug)]
+pub struct Sensor {
+    coord: Coord,
+    beacon: Coord,
+    closest_dis: CoordType,
+}
+
+impl Sensor {
+    fn min_x(&self) -> CoordType {
+        self.coord.0 - self.closest_dis
+    }
+    fn max_x(&self) -> CoordType {
+        self.coord.0 + self.closest_dis
+    }
+    #[allow(dead_code)]
+    fn min_y(&self) -> CoordType {
+        self.coord.1 - self.closest_dis
+    }
+    #[allow(dead_code)]
+    fn max_y(&self) -> CoordType {
+        self.coord.1 + self.closest_dis
+    }
+
+    fn dis(&self, c: Coord) -> CoordType {
+        (self.coord.0 - c.0).abs() + (self.coord.1 - c.1).abs()
+    }
+
+    fn could_be_beacon(&self, c: Coord) -> bool {
+        // There is never a tie where two beacons are the same distance to a sensor.
+        !self.reaches(c) || c == self.beacon
+    }
+
+    /// Whether `c` is as close to the sensor as its beacon, or closer.
+    fn reaches(&self, c: Coord) -> bool {
+        self.dis(c) <= self.closest_dis
+    }
+
+    fn furthest_horizontal(&self, c: Coord) -> Coord {
+        let y = c.1;
+        let x1 = self.closest_dis - (y - self.coord.1).abs() + self.coord.0;
+        let x2 = -(self.closest_dis - (y - self.coord.1).abs()) + self.coord.0;
+        (x1.max(x2), y)
+    }
+
+    #[allow(dead_code)]
+    fn furthest_vertical(&self, c: Coord) -> Coord {
+        let x = c.0;
+        let y1 = self.closest_dis - (x - self.coord.0).abs() + self.coord.1;
+        let y2 = -(self.closest_dis - (x - self.coord.0).abs()) + self.coord.1;
+        (x, y1.max(y2))
+    }
+
+    fn interval_at(&self, y: CoordType) -> Option<(CoordType, CoordType)> {
+        if (y - self.coord.1).abs() > self.closest_dis {
+            return None;
+        }
+        let x1 = self.closest_dis - (y - self.coord.1).abs() + self.coord.0;
+        let x2 = -(self.closest_dis - (y - self.coord.1).abs()) + self.coord.0;
+        Some((x1.min(x2), x1.max(x2)))
+    }
+}
+
+/// Coordinates are kept far from the limits of `CoordType`, so distances can not overflow.
+const MAX_COORD: CoordType = 1 << 40;
+
+pub fn parse_report(s: &str) -> Result<Vec<Sensor>> {
+    lazy_static! {
+        static ref RE: Regex = Regex::new(
+            r"^Sensor at x=(-?\d+), y=(-?\d+): closest beacon is at x=(-?\d+), y=(-?\d+)$"
+        )
+        .unwrap();
+    }
+    let mut reports = Vec::new();
+    for (i, line) in s.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let Some(cap) = RE.captures(line) else {
+            return parse_err!("This is not a report: {:?}", line).at_line(i + 1);
+        };
+        let mut coords = [0; 4];
+        for (c, m) in coords.iter_mut().zip(cap.iter().skip(1).flatten()) {
+            *c = m.as_str().parse::<CoordType>().at_line(i + 1)?;
+            if c.abs() > MAX_COORD {
+                return parse_err!("{c} is too far away").at_line(i + 1);
+            }
+        }
+        let [s_x, s_y, b_x, b_y] = coords;
+        let (sensor, beacon) = ((s_x, s_y), (b_x, b_y));
+        reports.push(Sensor {
+            coord: sensor,
+            beacon,
+            closest_dis: (sensor.0 - beacon.0).abs() + (sensor.1 - beacon.1).abs(),
+        });
+    }
+    Ok(reports)
+}
+
+fn generate(rng: &mut Rng, sensors: usize, _knobs: &Knobs) -> Result<String> {
+    Ok(report_in_square(rng, sensors, 4000000))
+}
+
+/// The distress beacon is hidden in the square from 0 to `max` searched by part 2, and every
+/// sensor reaches up to the beacon next to it, on the side of the sensor.
+///
+/// The sensors in the corners of the square see all of it but the distress beacon.
+fn report_in_square(rng: &mut Rng, sensors: usize, max: CoordType) -> String {
+    let distress = (rng.between(1, max - 1), rng.between(1, max - 1));
+    let mut coords = vec![(0, 0), (max, 0), (0, max), (max, max)];
+    while coords.len() < sensors {
+        let c = (rng.between(0, max), rng.between(0, max));
+        // right above or below the distress beacon, both beacons are as close
+        if c.0 != distress.0 {
+            coords.push(c);
+        }
+    }
+    rng.shuffle(&mut coords);
+    let reports: Vec<String> = coords
+        .into_iter()
+        .map(|(x, y)| {
+            let beacon = (distress.0 + (x - distress.0).signum(), distress.1);
+            format!(
+                "Sensor at x={x}, y={y}: closest beacon is at x={}, y={}",
+                beacon.0, beacon.1
+            )
+        })
+        .collect();
+    reports.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use aoc_common::Rng;
+    use proptest::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn example_input() {
+        let input = "Sensor at x=2, y=18: closest beacon is at x=-2, y=15
+        Sensor at x=9, y=16: closest beacon is at x=10, y=16
+        Sensor at x=13, y=2: closest beacon is at x=15, y=3
+        Sensor at x=12, y=14: closest beacon is at x=10, y=16
+        Sensor at x=10, y=20: closest beacon is at x=10, y=16
+        Sensor at x=14, y=17: closest beacon is at x=10, y=16
+        Sensor at x=8, y=7: closest beacon is at x=2, y=10
+        Sensor at x=2, y=0: closest beacon is at x=2, y=10
+        Sensor at x=0, y=11: closest beacon is at x=2, y=10
+        Sensor at x=20, y=14: closest beacon is at x=25, y=17
+        Sensor at x=17, y=20: closest beacon is at x=21, y=22
+        Sensor at x=16, y=7: closest beacon is at x=15, y=3
+        Sensor at x=14, y=3: closest beacon is at x=15, y=3
+        Sensor at x=20, y=1: closest beacon is at x=15, y=3";
+        let sensors = parse_report(input).unwrap();
+
+        assert_eq!(sensors[6].coord, (8, 7));
+        assert!(sensors[6].could_be_beacon((2, 10)));
+        assert!(!sensors[6].could_be_beacon((3, 10)));
+        assert!(!sensors[6].could_be_beacon((8, 16)));
+        assert_eq!(26, part1(&sensors, 10).unwrap());
+        assert_eq!(26, part1_with_interval(&sensors, 10).unwrap());
+        assert_eq!(56000011, part2(&sensors, 20).unwrap());
+        assert_eq!(56000011, part2_with_interval(&sensors, 20).unwrap());
+    }
+
+    #[test]
+    fn touching_sensors() {
+        let input = "Sensor at x=0, y=0: closest beacon is at x=10, y=0
+        Sensor at x=21, y=0: closest beacon is at x=31, y=0";
+        let sensors = parse_report(input).unwrap();
+        assert_eq!(40000001, part2_with_interval(&sensors, 20).unwrap());
+        assert!(part2_with_interval(&sensors[..1], 5).is_err());
+    }
+
+    #[test]
+    fn gaps_out_of_bounds() {
+        // the columns -4 to -1 are left before 11, but out of the search
+        let input = "Sensor at x=-10, y=0: closest beacon is at x=-5, y=0
+        Sensor at x=5, y=0: closest beacon is at x=0, y=0
+        Sensor at x=17, y=0: closest beacon is at x=12, y=0";
+        let sensors = parse_report(input).unwrap();
+        assert_eq!(44000000, part2_with_interval(&sensors, 20).unwrap());
+    }
+
+    proptest! {
+        #[test]
+        fn intervals_agree_with_scan(
+            sensors in 4usize..12,
+            max in 2i64..60,
+            row in 0.0..=1.0,
+            seed: u64,
+        ) {
+            let input = report_in_square(&mut Rng::new(seed), sensors, max);
+            let sensors = parse_input(&input).unwrap();
+            let y = (row * max as f64) as i64;
+            prop_assert_eq!(part1(&sensors, y).unwrap(), part1_with_interval(&sensors, y).unwrap());
+            prop_assert_eq!(
+                part2(&sensors, max).unwrap(),
+                part2_with_interval(&sensors, max).unwrap()
+            );
+        }
+    }
+}
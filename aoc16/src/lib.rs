@@ -0,0 +1,503 @@
+use std::collections::HashMap;
+
+use aoc_common::{parse_err, Answer, Generator, Knob, Knobs, Position, Result, Rng, Solution};
+use aoc_search::bfs;
+use tracing::{debug, debug_span, trace};
+
+pub struct Day16;
+
+impl Solution for Day16 {
+    type Input = (Vec<Valve>, usize);
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        let (valves, aa_id, _) = parse_input(input)?;
+        Ok((valves, aa_id))
+    }
+
+    fn part1((valves, aa_id): &Self::Input) -> Result<Answer> {
+        part1(valves, *aa_id).map(Answer::from)
+    }
+
+    fn part2((valves, aa_id): &Self::Input) -> Result<Answer> {
+        part2(valves, *aa_id).map(Answer::from)
+    }
+
+    fn generator() -> Option<Generator> {
+        Some(Generator {
+            size: "valves, at most 64",
+            default_size: 58,
+            knobs: &[Knob {
+                name: "flowing",
+                about: "number of valves with a flow rate",
+                default: "15",
+            }],
+            generate,
+        })
+    }
+}
+
+// memoize the pressure still to be released from a state, not the total, since the total depends
+// on how the state was reached
+type Memo = HashMap<(usize, usize, usize, u64), usize>;
+type PairMemo = HashMap<((usize, usize), (usize, usize), (usize, usize), u64), usize>;
+
+pub fn part1(valves: &[Valve], aa_id: usize) -> Result<usize> {
+    let mut memorization = vec![vec![usize::MAX; valves.len()]; valves.len()];
+    for i in 0..valves.len() {
+        for j in 0..valves.len() {
+            shortest_dis_bfs(&mut memorization, valves, i, j);
+        }
+    }
+    let closed: Vec<usize> = valves
+        .iter()
+        .enumerate()
+        .filter(|(_, v)| v.flow_rate > 0)
+        .map(|(i, _)| i)
+        .collect();
+    let _span = debug_span!("search", part = 1, valves = closed.len()).entered();
+    let mut memo = HashMap::new();
+    let total_pressure = dp(
+        &memorization,
+        &closed,
+        0,
+        valves,
+        aa_id,
+        0,
+        0,
+        0,
+        30,
+        &mut memo,
+    );
+    debug!(memo = memo.len(), total_pressure, "search done");
+
+    Ok(total_pressure)
+}
+
+pub fn part2(valves: &[Valve], aa_id: usize) -> Result<usize> {
+    let mut memorization = vec![vec![usize::MAX; valves.len()]; valves.len()];
+    for i in 0..valves.len() {
+        for j in 0..valves.len() {
+            shortest_dis_bfs(&mut memorization, valves, i, j);
+        }
+    }
+    let mut closed: Vec<usize> = valves
+        .iter()
+        .enumerate()
+        .filter(|(_, v)| v.flow_rate > 0)
+        .map(|(i, _)| i)
+        .collect();
+    closed.sort_by(|&id1, &id2| valves[id2].flow_rate.cmp(&valves[id1].flow_rate));
+    let _span = debug_span!("search", part = 2, valves = closed.len()).entered();
+    let mut memo: PairMemo = HashMap::new();
+    let mut memo2: Memo = HashMap::new();
+    let total_pressure = dp_part2(
+        &memorization,
+        &closed,
+        0,
+        valves,
+        (aa_id, aa_id),
+        (0, 0),
+        (0, 0),
+        (0, 0),
+        26,
+        &mut memo,
+        &mut memo2,
+    );
+    debug!(
+        memo = memo.len(),
+        single_memo = memo2.len(),
+        total_pressure,
+        "search done"
+    );
+
+    Ok(total_pressure)
+}
+
+fn shortest_dis_bfs(memorization: &mut [Vec<usize>], valves: &[Valve], start: usize, dest: usize) {
+    if memorization[start][dest] != usize::MAX {
+        return;
+    }
+    if let Some(path) = bfs(
+        [start],
+        |&cur| valves[cur].dest.iter().copied(),
+        |&cur| cur == dest,
+    ) {
+        memorization[start][dest] = path.cost;
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn dp(
+    memorization: &[Vec<usize>],
+    closed: &[usize],
+    opened: u64,
+    valves: &[Valve],
+    id: usize,
+    total_pressure: usize,
+    pressure: usize,
+    time: usize,
+    time_limit: usize,
+    memo: &mut Memo,
+) -> usize {
+    // the depth of the recursion is the number of opened valves
+    let depth = opened.count_ones();
+    if let Some(&future) = memo.get(&(id, pressure, time, opened)) {
+        trace!(depth, valve = id, time, "memo hit");
+        return total_pressure + future;
+    }
+    trace!(depth, valve = id, time, "memo miss");
+    if opened == closed.iter().fold(0, |bit, i| bit | (1 << i)) {
+        return total_pressure + (time_limit - time) * pressure;
+    }
+    let result = closed
+        .iter()
+        .map(|&next| {
+            if opened & (1 << next) == 0 {
+                let mut new_opend = opened;
+                new_opend |= 1 << next;
+                let d = memorization[id][next] + 1;
+                if time + d > time_limit {
+                    trace!(depth, valve = next, "pruned, out of time");
+                    total_pressure + (time_limit - time) * pressure
+                } else {
+                    dp(
+                        memorization,
+                        closed,
+                        new_opend,
+                        valves,
+                        next,
+                        total_pressure + pressure * d,
+                        pressure + valves[next].flow_rate,
+                        time + d,
+                        time_limit,
+                        memo,
+                    )
+                }
+            } else {
+                0
+            }
+        })
+        .max()
+        .unwrap();
+    memo.insert((id, pressure, time, opened), result - total_pressure);
+    result
+}
+
+#[allow(clippy::too_many_arguments)]
+fn dp_part2(
+    memorization: &[Vec<usize>],
+    closed: &[usize],
+    opened: u64,
+    valves: &[Valve],
+    id: (usize, usize),
+    total_pressure: (usize, usize),
+    pressure: (usize, usize),
+    time: (usize, usize),
+    time_limit: usize,
+    memo: &mut PairMemo,
+    memo2: &mut Memo,
+) -> usize {
+    let depth = opened.count_ones();
+    if let Some(&future) = memo.get(&(id, pressure, time, opened)) {
+        trace!(depth, valves = ?id, time = ?time, "memo hit");
+        return total_pressure.0 + total_pressure.1 + future;
+    }
+    trace!(depth, valves = ?id, time = ?time, "memo miss");
+    if opened == closed.iter().fold(0, |bit, i| bit | (1 << i)) {
+        return total_pressure.0
+            + total_pressure.1
+            + (time_limit - time.0) * pressure.0
+            + (time_limit - time.1) * pressure.1;
+    }
+    // both stop opening valves
+    let mut result = total_pressure.0
+        + total_pressure.1
+        + pressure.0 * (time_limit - time.0)
+        + pressure.1 * (time_limit - time.1);
+    // with a single valve left, only one of the two can open it
+    let last_one = closed.iter().filter(|&&v| opened & (1 << v) == 0).count() == 1;
+
+    let l = closed.len();
+    for id0 in 0..l {
+        let next0 = closed[id0];
+        for id1 in 0..l {
+            let next1 = closed[id1];
+            if next0 == next1 && !last_one {
+                continue;
+            }
+            if opened & (1 << next0) != 0 || opened & (1 << next1) != 0 {
+                continue;
+            }
+            let d0 = memorization[id.0][next0] + 1;
+            let d1 = memorization[id.1][next1] + 1;
+            if next0 != next1 && time.0 + d0 <= time_limit && time.1 + d1 <= time_limit {
+                let mut new_opened = opened;
+                new_opened |= 1 << next0;
+                new_opened |= 1 << next1;
+                result = result.max(dp_part2(
+                    memorization,
+                    closed,
+                    new_opened,
+                    valves,
+                    (next0, next1),
+                    (
+                        total_pressure.0 + d0 * pressure.0,
+                        total_pressure.1 + d1 * pressure.1,
+                    ),
+                    (
+                        pressure.0 + valves[next0].flow_rate,
+                        pressure.1 + valves[next1].flow_rate,
+                    ),
+                    (time.0 + d0, time.1 + d1),
+                    time_limit,
+                    memo,
+                    memo2,
+                ));
+            } else if d0 + time.0 > time_limit && d1 + time.1 > time_limit {
+                trace!(depth, valves = ?(next0, next1), "pruned, out of time");
+                result = result.max(
+                    total_pressure.0
+                        + total_pressure.1
+                        + pressure.0 * (time_limit - time.0)
+                        + pressure.1 * (time_limit - time.1),
+                )
+            } else {
+                let (total_pressure, pressure, time, d0, next0) = if d0 + time.0 > d1 + time.1 {
+                    (
+                        rev_tuple(total_pressure),
+                        rev_tuple(pressure),
+                        rev_tuple(time),
+                        d1,
+                        next1,
+                    )
+                } else {
+                    (total_pressure, pressure, time, d0, next0)
+                };
+                let mut new_opened = opened;
+                new_opened |= 1 << next0;
+                result = result.max(
+                    total_pressure.1
+                        + pressure.1 * (time_limit - time.1)
+                        + dp(
+                            memorization,
+                            closed,
+                            new_opened,
+                            valves,
+                            next0,
+                            total_pressure.0 + pressure.0 * d0,
+                            pressure.0 + valves[next0].flow_rate,
+                            time.0 + d0,
+                            time_limit,
+                            memo2,
+                        ),
+                );
+            }
+        }
+    }
+    let future = result - total_pressure.0 - total_pressure.1;
+    memo.insert((id, pressure, time, opened), future);
+    memo.insert(
+        (rev_tuple(id), rev_tuple(pressure), rev_tuple(time), opened),
+        future,
+    );
+    result
+}
+
+fn rev_tuple(t: (usize, usize)) -> (usize, usize) {
+    (t.1, t.0)
+}
+
+#[derive(Debug)]
+pub struct Valve {
+    flow_rate: usize,
+    dest: Vec<usize>,
+}
+
+impl Valve {
+    fn new(flow_rate: usize, dest: Vec<usize>) -> Self {
+        Self { flow_rate, dest }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+/// Keeps the pressure released in 30 minutes by 64 valves far from overflowing, with a 32-bit
+/// `usize` too.
+const MAX_FLOW_RATE: usize = usize::MAX >> 16;
+
+pub fn parse_input(input: &str) -> Result<(Vec<Valve>, usize, HashMap<&str, usize>)> {
+    fn get_id<'a>(
+        name: &'a str,
+        valves_index: &mut HashMap<&'a str, usize>,
+        index: &mut usize,
+    ) -> usize {
+        if let Some(&id) = valves_index.get(name) {
+            id
+        } else {
+            valves_index.insert(name, *index);
+            *index += 1;
+            *index - 1
+        }
+    }
+
+    let mut valves_index: HashMap<&str, usize> = HashMap::new();
+    let mut index = 0;
+    let mut valves: Vec<Option<Valve>> = Vec::new();
+    for (i, line) in input.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let Some((name, rate, dest)) = line
+            .split_once("; ")
+            .and_then(|(part1, part2)| Some((part1.split_once(" has flow rate=")?, part2)))
+            .and_then(|((name, rate), part2)| {
+                let dest = part2
+                    .strip_prefix("tunnels lead to valves ")
+                    .or_else(|| part2.strip_prefix("tunnel leads to valve "))?;
+                Some((name.strip_prefix("Valve ")?, rate, dest))
+            })
+        else {
+            return parse_err!("This is not a valve: {:?}", line).at_line(i + 1);
+        };
+        let rate: usize = rate.parse().at_line(i + 1)?;
+        if rate > MAX_FLOW_RATE {
+            return parse_err!("the flow rate {rate} is too high").at_line(i + 1);
+        }
+        let id = get_id(name, &mut valves_index, &mut index);
+        let dest: Vec<_> = dest
+            .split(", ")
+            .map(|n| get_id(n, &mut valves_index, &mut index))
+            .collect();
+        if valves.len() < index {
+            valves.resize_with(index, || None);
+        }
+        if valves[id].is_some() {
+            return parse_err!("valve {name} is already scanned").at_line(i + 1);
+        }
+        valves[id] = Some(Valve::new(rate, dest));
+    }
+    if valves.len() > 64 {
+        return parse_err!("there are {} valves, more than 64", valves.len());
+    }
+    let Some(&aa_id) = valves_index.get("AA") else {
+        return parse_err!("there is no valve AA");
+    };
+    if let Some((name, _)) = valves_index.iter().find(|(_, &id)| valves[id].is_none()) {
+        return parse_err!("tunnel to valve {name}, which is not scanned");
+    }
+    Ok((valves.into_iter().flatten().collect(), aa_id, valves_index))
+}
+
+/// Like in the real input, the valves with a flow rate and AA are joined by corridors of valves
+/// without one, and AA never flows.
+fn generate(rng: &mut Rng, valves: usize, knobs: &Knobs) -> Result<String> {
+    let count = valves.clamp(2, 64);
+    let flowing = knobs.get::<usize>("flowing")?.min(count - 1);
+    let mut names = vec!["AA".to_string()];
+    while names.len() < count {
+        let name: String = (0..2)
+            .map(|_| (b'A' + rng.below(26) as u8) as char)
+            .collect();
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+    // AA is valve 0, the valves that flow come right after it
+    let mut rates = vec![0; count];
+    for rate in &mut rates[1..=flowing] {
+        *rate = rng.between(3, 25);
+    }
+    let mut corridors: Vec<Vec<usize>> = (1..=flowing)
+        .map(|valve| vec![rng.index(valve), valve])
+        .collect();
+    for _ in 0..flowing / 3 {
+        let (a, b) = (rng.index(flowing + 1), rng.index(flowing + 1));
+        if a != b {
+            corridors.push(vec![a, b]);
+        }
+    }
+    for valve in flowing + 1..count {
+        if corridors.is_empty() {
+            corridors.push(vec![0, valve]);
+        } else {
+            let corridor = rng.index(corridors.len());
+            corridors[corridor].insert(1, valve);
+        }
+    }
+    let mut tunnels = vec![vec![]; count];
+    for corridor in &corridors {
+        for pair in corridor.windows(2) {
+            if !tunnels[pair[0]].contains(&pair[1]) {
+                tunnels[pair[0]].push(pair[1]);
+                tunnels[pair[1]].push(pair[0]);
+            }
+        }
+    }
+
+    let mut lines: Vec<String> = (0..count)
+        .map(|valve| {
+            let dest: Vec<&str> = tunnels[valve].iter().map(|&d| names[d].as_str()).collect();
+            let lead = if dest.len() == 1 {
+                "tunnel leads to valve"
+            } else {
+                "tunnels lead to valves"
+            };
+            format!(
+                "Valve {} has flow rate={}; {lead} {}",
+                names[valve],
+                rates[valve],
+                dest.join(", ")
+            )
+        })
+        .collect();
+    rng.shuffle(&mut lines);
+    Ok(lines.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn example_input() {
+        use crate::*;
+        let input = "Valve AA has flow rate=0; tunnels lead to valves DD, II, BB
+        Valve BB has flow rate=13; tunnels lead to valves CC, AA
+        Valve CC has flow rate=2; tunnels lead to valves DD, BB
+        Valve DD has flow rate=20; tunnels lead to valves CC, AA, EE
+        Valve EE has flow rate=3; tunnels lead to valves FF, DD
+        Valve FF has flow rate=0; tunnels lead to valves EE, GG
+        Valve GG has flow rate=0; tunnels lead to valves FF, HH
+        Valve HH has flow rate=22; tunnel leads to valve GG
+        Valve II has flow rate=0; tunnels lead to valves AA, JJ
+        Valve JJ has flow rate=21; tunnel leads to valve II";
+        let (valves, aa_id, valves_index) = parse_input(input).unwrap();
+        println!("{:?}", valves_index);
+        assert_eq!(aa_id, 0);
+        assert_eq!(valves.len(), input.lines().count());
+        assert_eq!(part1(&valves, aa_id).unwrap(), 1651);
+        assert_eq!(part2(&valves, aa_id).unwrap(), 1707);
+    }
+
+    #[test]
+    fn test_shortest_path() {
+        use crate::*;
+        let input = "Valve AA has flow rate=0; tunnels lead to valves BB, CC, DD
+        Valve BB has flow rate=13; tunnels lead to valves CC, DD
+        Valve CC has flow rate=2; tunnels lead to valves DD
+        Valve DD has flow rate=20; tunnels lead to valves FF
+        Valve FF has flow rate=20; tunnels lead to valves FF";
+        let (valves, _, all_index) = parse_input(input).unwrap();
+        dbg!(all_index);
+        let mut memorization = vec![vec![usize::MAX; valves.len()]; valves.len()];
+        for i in 0..valves.len() {
+            for j in 0..valves.len() {
+                shortest_dis_bfs(&mut memorization, &valves, i, j);
+            }
+        }
+        assert_eq!(memorization[0][4], 2);
+        assert_eq!(memorization[0][3], 1);
+        assert_eq!(memorization[0][2], 1);
+        assert_eq!(memorization[0][1], 1);
+        assert_eq!(memorization[0][0], 0);
+        assert_eq!(memorization[1][4], 2);
+    }
+}
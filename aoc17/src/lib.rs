@@ -0,0 +1,412 @@
+use std::collections::HashMap;
+use std::collections::HashSet;
+
+use aoc_common::{parse_err, Animation, Answer, Generator, Knobs, Position, Result, Rng, Solution};
+use tracing::{debug, debug_span, trace};
+
+pub struct Day17;
+
+impl Solution for Day17 {
+    type Input = Vec<char>;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_input(input)
+    }
+
+    fn part1(jets: &Self::Input) -> Result<Answer> {
+        part1(jets, 2022).map(Answer::from)
+    }
+
+    fn part2(jets: &Self::Input) -> Result<Answer> {
+        part2(jets, 1000000000000).map(Answer::from)
+    }
+
+    fn animations() -> Vec<Animation> {
+        vec![Animation {
+            name: "rock tower",
+            part: 1,
+            animate: |input, screen| {
+                let jets = parse_input(input)?;
+                simulate(&jets, 2022, false, |chamber, rock| {
+                    screen.show(&draw_chamber(chamber, rock, 30))
+                })?;
+                Ok(())
+            },
+        }]
+    }
+
+    fn generator() -> Option<Generator> {
+        Some(Generator {
+            size: "jets",
+            default_size: 10091,
+            knobs: &[],
+            generate,
+        })
+    }
+}
+
+type Coord = (i64, i64);
+
+pub fn parse_input(input: &str) -> Result<Vec<char>> {
+    let input = input.trim();
+    if input.is_empty() {
+        return parse_err!("there is no jet");
+    }
+    if let Some((i, c)) = input.char_indices().find(|&(_, c)| c != '<' && c != '>') {
+        return parse_err!("{c:?} is not a jet").at(1, i + 1);
+    }
+    Ok(input.chars().flat_map(|c| [c, 'v']).collect())
+}
+
+pub fn part1(jets: &[char], total_rock: i64) -> Result<i64> {
+    let highest_rock = rock_tower(jets, total_rock)?;
+    Ok(highest_rock)
+}
+
+pub fn part2(jets: &[char], total_rock: i64) -> Result<i64> {
+    let highest_rock = rock_tower(jets, total_rock)?;
+    Ok(highest_rock)
+}
+
+fn rock_tower(jets: &[char], total_rock: i64) -> Result<i64> {
+    simulate(jets, total_rock, true, |_, _| true)
+}
+
+/// How deep below the top of the tower the shape of the surface counts for finding a cycle.
+const MAX_DEPTH: i64 = 64;
+
+/// Drops `total_rock` rocks and returns the height of the tower.
+///
+/// With `skip_cycles` the height is computed as soon as the tower repeats itself. `on_move` is
+/// called before every push and fall of a rock, the simulation stops when it returns `false`.
+fn simulate(
+    jets: &[char],
+    total_rock: i64,
+    skip_cycles: bool,
+    mut on_move: impl FnMut(&HashSet<Coord>, &Rock) -> bool,
+) -> Result<i64> {
+    let _span = debug_span!("simulate", total_rock, jets = jets.len()).entered();
+    let mut chamber: HashSet<Coord> = HashSet::new();
+
+    use RockShape::*;
+    let rocks = [Ih, X, J, Iv, O];
+    let mut jets = jets.iter().enumerate().cycle();
+
+    let mut memorization: HashMap<(usize, usize, Vec<i64>), (i64, i64)> = HashMap::new();
+    let mut highests = [0; 7];
+
+    let mut rock_count = 0;
+    let mut highest_rock = 0;
+    for (shape_id, &shape) in rocks.iter().enumerate().cycle() {
+        if rock_count == total_rock {
+            break;
+        }
+
+        let mut rock = Rock::new(shape, highest_rock);
+        rock_count += 1;
+
+        for (jet_id, &movement) in jets.by_ref() {
+            if !on_move(&chamber, &rock) {
+                return Ok(highest_rock);
+            }
+            match movement {
+                '<' => {
+                    let next_rock = rock.push_left();
+                    if !next_rock.is_left_collided(&chamber) {
+                        rock = next_rock;
+                    }
+                }
+                '>' => {
+                    let next_rock = rock.push_right();
+                    if !next_rock.is_right_collided(&chamber) {
+                        rock = next_rock;
+                    }
+                }
+                'v' => {
+                    let next_rock = rock.fall_down();
+                    if next_rock.is_bottom_collided(&chamber) {
+                        rock.occupy().into_iter().for_each(|c| {
+                            chamber.insert(c);
+                        });
+                        highest_rock =
+                            highest_rock.max(rock.top().iter().map(|(_, y)| y).max().unwrap() + 1);
+                        for (x, y) in rock.top() {
+                            highests[x as usize] = highests[x as usize].max(y + 1);
+                        }
+                        // a column no rock ever reaches would make the key grow forever, and no
+                        // rock falls this deep anyway
+                        let key = (
+                            shape_id,
+                            jet_id,
+                            highests
+                                .iter()
+                                .map(|h| (highest_rock - h).min(MAX_DEPTH))
+                                .collect(),
+                        );
+                        if let Some((last_rock, last_highest)) = memorization
+                            .insert(key, (rock_count, highest_rock))
+                            .filter(|_| skip_cycles)
+                        {
+                            let cycle_length = rock_count - last_rock;
+                            let skip_cycle_count = (total_rock - rock_count) / cycle_length;
+                            trace!(rock_count, cycle_length, "surface seen before");
+                            if total_rock % cycle_length == rock_count % cycle_length {
+                                assert_eq!(
+                                    total_rock,
+                                    rock_count + skip_cycle_count * cycle_length
+                                );
+                                debug!(
+                                    rock_count,
+                                    cycle_length,
+                                    cycle_height = highest_rock - last_highest,
+                                    skipped_cycles = skip_cycle_count,
+                                    memo = memorization.len(),
+                                    "cycle found"
+                                );
+                                return Ok(
+                                    skip_cycle_count * (highest_rock - last_highest) + highest_rock
+                                );
+                            }
+                        }
+                        break;
+                    };
+                    rock = next_rock;
+                }
+                _ => unreachable!(),
+            };
+        }
+    }
+    debug!(memo = memorization.len(), "no cycle skipped");
+    Ok(highest_rock)
+}
+
+/// Draws `rows` rows of the chamber down from the top of the falling rock.
+fn draw_chamber(chamber: &HashSet<Coord>, rock: &Rock, rows: i64) -> String {
+    let falling = rock.occupy();
+    let top = rock.top_left_pos.1;
+    let bottom = (top - rows + 1).max(0);
+    let mut s = String::new();
+    for y in (bottom..=top).rev() {
+        s.push('|');
+        for x in 0..7 {
+            if falling.contains(&(x, y)) {
+                s.push('@')
+            } else if chamber.contains(&(x, y)) {
+                s.push('#')
+            } else {
+                s.push('.')
+            }
+        }
+        s.push_str("|\n");
+    }
+    if bottom == 0 {
+        s.push_str("+-------+\n");
+    }
+    s
+}
+
+#[derive(Debug)]
+struct Rock {
+    shape: RockShape,
+    top_left_pos: Coord,
+}
+
+impl Rock {
+    fn new(shape: RockShape, highest_rock: i64) -> Self {
+        use RockShape::*;
+        match shape {
+            Ih => Self {
+                shape,
+                top_left_pos: (2, highest_rock + 3),
+            },
+            Iv => Self {
+                shape,
+                top_left_pos: (2, highest_rock + 6),
+            },
+            J => Self {
+                shape,
+                top_left_pos: (2, highest_rock + 5),
+            },
+            O => Self {
+                shape,
+                top_left_pos: (2, highest_rock + 4),
+            },
+            X => Self {
+                shape,
+                top_left_pos: (2, highest_rock + 5),
+            },
+        }
+    }
+
+    fn occupy(&self) -> Vec<Coord> {
+        let (x, y) = self.top_left_pos;
+        match self.shape {
+            RockShape::Ih => vec![(x, y), (x + 1, y), (x + 2, y), (x + 3, y)],
+            RockShape::Iv => vec![(x, y), (x, y - 1), (x, y - 2), (x, y - 3)],
+            RockShape::J => vec![
+                (x + 2, y),
+                (x + 2, y - 1),
+                (x, y - 2),
+                (x + 1, y - 2),
+                (x + 2, y - 2),
+            ],
+            RockShape::O => vec![(x, y), (x + 1, y), (x, y - 1), (x + 1, y - 1)],
+            RockShape::X => {
+                vec![
+                    (x + 1, y),
+                    (x, y - 1),
+                    (x + 1, y - 1),
+                    (x + 2, y - 1),
+                    (x + 1, y - 2),
+                ]
+            }
+        }
+    }
+
+    fn top(&self) -> Vec<Coord> {
+        let (x, y) = self.top_left_pos;
+        match self.shape {
+            RockShape::Ih => vec![(x, y), (x + 1, y), (x + 2, y), (x + 3, y)],
+            RockShape::Iv => vec![(x, y)],
+            RockShape::J => vec![(x + 2, y), (x, y - 2), (x + 1, y - 2)],
+            RockShape::O => vec![(x, y), (x + 1, y)],
+            RockShape::X => {
+                vec![(x + 1, y), (x, y - 1), (x + 2, y - 1)]
+            }
+        }
+    }
+
+    fn left(&self) -> Vec<Coord> {
+        let (x, y) = self.top_left_pos;
+        match self.shape {
+            RockShape::Ih => vec![(x, y)],
+            RockShape::Iv => vec![(x, y), (x, y - 1), (x, y - 2), (x, y - 3)],
+            RockShape::J => vec![(x, y - 2), (x + 2, y), (x + 2, y - 1)],
+            RockShape::O => vec![(x, y), (x, y - 1)],
+            RockShape::X => {
+                vec![(x + 1, y), (x, y - 1), (x + 1, y - 2)]
+            }
+        }
+    }
+
+    fn right(&self) -> Vec<Coord> {
+        let (x, y) = self.top_left_pos;
+        match self.shape {
+            RockShape::Ih => vec![(x + 3, y)],
+            RockShape::Iv => vec![(x, y), (x, y - 1), (x, y - 2), (x, y - 3)],
+            RockShape::J => vec![(x + 2, y), (x + 2, y - 1), (x + 2, y - 2)],
+            RockShape::O => vec![(x + 1, y), (x + 1, y - 1)],
+            RockShape::X => {
+                vec![(x + 1, y), (x + 2, y - 1), (x + 1, y - 2)]
+            }
+        }
+    }
+
+    fn bottom(&self) -> Vec<Coord> {
+        let (x, y) = self.top_left_pos;
+        match self.shape {
+            RockShape::Ih => vec![(x, y), (x + 1, y), (x + 2, y), (x + 3, y)],
+            RockShape::Iv => vec![(x, y - 3)],
+            RockShape::J => vec![(x, y - 2), (x + 1, y - 2), (x + 2, y - 2)],
+            RockShape::O => vec![(x, y - 1), (x + 1, y - 1)],
+            RockShape::X => {
+                vec![(x, y - 1), (x + 2, y - 1), (x + 1, y - 2)]
+            }
+        }
+    }
+
+    fn push_left(&self) -> Self {
+        Self {
+            shape: self.shape,
+            top_left_pos: (self.top_left_pos.0 - 1, self.top_left_pos.1),
+        }
+    }
+
+    fn push_right(&self) -> Self {
+        Self {
+            shape: self.shape,
+            top_left_pos: (self.top_left_pos.0 + 1, self.top_left_pos.1),
+        }
+    }
+
+    fn fall_down(&self) -> Self {
+        Self {
+            shape: self.shape,
+            top_left_pos: (self.top_left_pos.0, self.top_left_pos.1 - 1),
+        }
+    }
+
+    fn is_bottom_collided(&self, chamber: &HashSet<Coord>) -> bool {
+        self.bottom().iter().any(|c| c.1 < 0 || chamber.contains(c))
+    }
+
+    fn is_left_collided(&self, chamber: &HashSet<Coord>) -> bool {
+        self.left().iter().any(|c| c.0 < 0 || chamber.contains(c))
+    }
+
+    fn is_right_collided(&self, chamber: &HashSet<Coord>) -> bool {
+        self.right().iter().any(|c| c.0 > 6 || chamber.contains(c))
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum RockShape {
+    Ih,
+    Iv,
+    J,
+    O,
+    X,
+}
+
+fn generate(rng: &mut Rng, jets: usize, _knobs: &Knobs) -> Result<String> {
+    Ok((0..jets.max(1))
+        .map(|_| if rng.chance(0.5) { '<' } else { '>' })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn example_input() {
+        use crate::*;
+
+        let input = ">>><<><>><<<>><>>><<<>>><<<><<<>><>><<>>";
+        let jets: Vec<_> = input.trim().chars().flat_map(|c| [c, 'v']).collect();
+        assert_eq!(rock_tower(&jets, 1).unwrap(), 1);
+        assert_eq!(rock_tower(&jets, 2).unwrap(), 4);
+        assert_eq!(rock_tower(&jets, 3).unwrap(), 6);
+        assert_eq!(rock_tower(&jets, 4).unwrap(), 7);
+        assert_eq!(rock_tower(&jets, 5).unwrap(), 9);
+        assert_eq!(rock_tower(&jets, 6).unwrap(), 10);
+        assert_eq!(part1(&jets, 2022).unwrap(), 3068);
+        assert_eq!(simulate(&jets, 2022, false, |_, _| true).unwrap(), 3068);
+        let mut frames = vec![];
+        simulate(&jets, 1, false, |chamber, rock| {
+            frames.push(draw_chamber(chamber, rock, 30));
+            true
+        })
+        .unwrap();
+        assert_eq!(
+            frames[0],
+            "|..@@@@.|\n|.......|\n|.......|\n|.......|\n+-------+\n"
+        );
+        assert_eq!(part2(&jets, 10000).unwrap(), 15148);
+        let cycle = 35;
+        let r = part2(&jets, cycle).unwrap();
+        assert_eq!(60, r);
+        assert_eq!(r + 53 * 3, part2(&jets, cycle * 4).unwrap());
+        assert_eq!(272, part2(&jets, cycle * 5).unwrap());
+        assert_eq!(1514285714288, part2(&jets, 1000000000000).unwrap());
+    }
+
+    #[test]
+    fn one_sided_jets() {
+        use crate::*;
+
+        for input in ["<", ">", "<<>", ">>><"] {
+            let jets = parse_input(input).unwrap();
+            let height = simulate(&jets, 2022, false, |_, _| true).unwrap();
+            assert_eq!(part1(&jets, 2022).unwrap(), height, "{input}");
+        }
+    }
+}
@@ -0,0 +1,295 @@
+use std::collections::HashSet;
+
+use aoc_common::{parse_err, Answer, Generator, Knobs, Position, Result, Rng, Solution, Variant};
+use aoc_search::flood_fill;
+
+pub struct Day18;
+
+impl Solution for Day18 {
+    type Input = Vec<Vertex>;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_input(input)
+    }
+
+    fn part1(cubes: &Self::Input) -> Result<Answer> {
+        part1(cubes).map(Answer::from)
+    }
+
+    fn part2(cubes: &Self::Input) -> Result<Answer> {
+        part2_with_flood_fill(cubes).map(Answer::from)
+    }
+
+    fn variants() -> Vec<Variant> {
+        vec![Variant {
+            name: "air pocket search",
+            part: 2,
+            solve: |input| part2(&parse_input(input)?).map(Answer::from),
+        }]
+    }
+
+    fn generator() -> Option<Generator> {
+        Some(Generator {
+            size: "cubes",
+            default_size: 2800,
+            knobs: &[],
+            generate,
+        })
+    }
+}
+
+type Coord = i32;
+pub type Vertex = (Coord, Coord, Coord);
+
+pub fn parse_input(input: &str) -> Result<Vec<Vertex>> {
+    let cubes = input
+        .lines()
+        .enumerate()
+        .map(|(i, l)| from_str(l).at_line(i + 1))
+        .collect::<Result<Vec<Vertex>>>()?;
+    if cubes.is_empty() {
+        return parse_err!("there is no cube");
+    }
+    Ok(cubes)
+}
+
+pub fn part1(cubes: &[Vertex]) -> Result<i32> {
+    let result = surface_area(cubes);
+    Ok(result)
+}
+
+pub fn part2(cubes: &[Vertex]) -> Result<i32> {
+    let cubes_set: HashSet<Vertex> = HashSet::from_iter(cubes.iter().cloned());
+    let vertices: HashSet<Vertex> = HashSet::from_iter(
+        cubes
+            .iter()
+            .flat_map(|&c| adjacent_vertices(c))
+            .filter(|p| !cubes_set.contains(p)),
+    );
+    let range = ranges(cubes);
+    let mut result = surface_area(cubes);
+    let mut visited = HashSet::new();
+    for vertex in vertices {
+        if !visited.contains(&vertex) {
+            let air = air_around(&cubes_set, vertex, range);
+            // air reaching out of the range is outside of the droplet
+            if air.iter().all(|&v| in_range(range, v)) {
+                result -= surface_area(&air.iter().copied().collect::<Vec<_>>());
+            }
+            visited.extend(air);
+        }
+    }
+    Ok(result)
+}
+
+/// The air connected to `vertex`, stopping at the first vertex out of the range.
+fn air_around(cubes: &HashSet<Vertex>, vertex: Vertex, range: (Vertex, Vertex)) -> HashSet<Vertex> {
+    flood_fill([vertex], |&cur| {
+        let mut next = adjacent_vertices(cur);
+        if in_range(range, cur) {
+            next.retain(|p| !cubes.contains(p));
+        } else {
+            next.clear();
+        }
+        next
+    })
+}
+
+pub fn part2_with_flood_fill(cubes: &[Vertex]) -> Result<i32> {
+    let cubes_set: HashSet<Vertex> = HashSet::from_iter(cubes.iter().cloned());
+    let range = ranges(cubes);
+    let result = flood(range.0, &cubes_set, range);
+
+    Ok(result)
+}
+
+fn flood(start: Vertex, cubes: &HashSet<Vertex>, range: (Vertex, Vertex)) -> i32 {
+    air_around(cubes, start, range)
+        .into_iter()
+        .filter(|&cur| in_range(range, cur))
+        .map(|cur| {
+            adjacent_vertices(cur)
+                .iter()
+                .filter(|next| cubes.contains(next))
+                .count() as i32
+        })
+        .sum()
+}
+
+fn surface_area(cubes: &[Vertex]) -> i32 {
+    let l = cubes.len();
+    if l == 0 {
+        return 0;
+    }
+    let mut result = l as i32 * 6;
+    for i in 0..l {
+        let c1 = cubes[i];
+        for &c2 in cubes.iter().skip(i + 1) {
+            if connected(c1, c2) {
+                result -= 2;
+            }
+        }
+    }
+    result
+}
+fn connected(v1: Vertex, v2: Vertex) -> bool {
+    dis(v1, v2) == 1
+}
+
+fn adjacent_vertices(vertex: Vertex) -> Vec<Vertex> {
+    let (x, y, z) = vertex;
+    vec![
+        (x - 1, y, z),
+        (x, y - 1, z),
+        (x, y, z - 1),
+        (x + 1, y, z),
+        (x, y + 1, z),
+        (x, y, z + 1),
+    ]
+}
+
+fn ranges(cubes: &[Vertex]) -> (Vertex, Vertex) {
+    let mut min_x = i32::MAX;
+    let mut min_y = i32::MAX;
+    let mut min_z = i32::MAX;
+    let mut max_x = i32::MIN;
+    let mut max_y = i32::MIN;
+    let mut max_z = i32::MIN;
+    for c in cubes {
+        min_x = min_x.min(c.0);
+        min_y = min_y.min(c.1);
+        min_z = min_z.min(c.2);
+        max_x = max_x.max(c.0);
+        max_y = max_y.max(c.1);
+        max_z = max_z.max(c.2);
+    }
+    (
+        (min_x - 1, min_y - 1, min_z - 1),
+        (max_x + 1, max_y + 1, max_z + 1),
+    )
+}
+
+fn in_range(range: (Vertex, Vertex), vertex: Vertex) -> bool {
+    dis(range.0, range.1) == dis(vertex, range.0) + dis(vertex, range.1)
+}
+
+fn dis(p1: Vertex, p2: Vertex) -> Coord {
+    (p1.0.abs_diff(p2.0) + p1.1.abs_diff(p2.1) + p1.2.abs_diff(p2.2)) as Coord
+}
+
+/// The searches look around the droplet, the coordinates must not overflow there.
+const MAX_COORD: Coord = 1000;
+
+fn from_str(s: &str) -> Result<Vertex> {
+    let coords: Vec<&str> = s.trim().split(',').collect();
+    if coords.len() == 3 {
+        let vertex: Vertex = (coords[0].parse()?, coords[1].parse()?, coords[2].parse()?);
+        if [vertex.0, vertex.1, vertex.2]
+            .iter()
+            .any(|c| c.abs() > MAX_COORD)
+        {
+            return parse_err!("the cube {} is too far away", s);
+        }
+        return Ok(vertex);
+    }
+    parse_err!("not a valid vertex: {}", s)
+}
+
+/// Cubes taken at random from a ball, about two thirds of it, which leaves some air trapped inside.
+fn generate(rng: &mut Rng, cubes: usize, _knobs: &Knobs) -> Result<String> {
+    let mut radius = 1;
+    let mut ball = loop {
+        let ball: Vec<Vertex> = (0..=2 * radius)
+            .flat_map(|x| {
+                (0..=2 * radius).flat_map(move |y| (0..=2 * radius).map(move |z| (x, y, z)))
+            })
+            .filter(|&c| {
+                let (x, y, z) = (c.0 - radius, c.1 - radius, c.2 - radius);
+                x * x + y * y + z * z <= radius * radius
+            })
+            .collect();
+        if ball.len() * 2 >= cubes * 3 {
+            break ball;
+        }
+        radius += 1;
+    };
+    rng.shuffle(&mut ball);
+    let lines: Vec<String> = ball[..cubes.max(1)]
+        .iter()
+        .map(|(x, y, z)| format!("{x},{y},{z}"))
+        .collect();
+    Ok(lines.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn example_input() {
+        let input = "2,2,2
+        1,2,2
+        3,2,2
+        2,1,2
+        2,3,2
+        2,2,1
+        2,2,3
+        2,2,4
+        2,2,6
+        1,2,5
+        3,2,5
+        2,1,5
+        2,3,5";
+        let cubes: Vec<Vertex> = input.lines().map(|l| from_str(l).unwrap()).collect();
+        assert_eq!(cubes.len(), input.lines().count());
+        assert!(connected((1, 1, 1), (2, 1, 1)));
+        assert!(connected((2, 1, 1), (2, 2, 1)));
+        assert!(!connected((1, 1, 1), (2, 2, 1)));
+        assert_eq!(part1(&cubes).unwrap(), 64);
+        assert_eq!(part2(&cubes).unwrap(), 58);
+        assert!(parse_input("").is_err());
+    }
+
+    #[test]
+    fn test_one_air_cube() {
+        let cubes = vec![
+            (1, 1, 1),
+            (2, 2, 1),
+            (3, 1, 1),
+            (2, 1, 2),
+            (2, 1, 0),
+            (2, 0, 1),
+        ];
+        assert_eq!(part2(&cubes).unwrap(), 30);
+    }
+
+    #[test]
+    fn test_two_air_cube() {
+        let cubes = vec![
+            (1, 1, 1),
+            (2, 1, 2),
+            (3, 1, 1),
+            (1, 1, 0),
+            (3, 1, 0),
+            (2, 1, -1),
+            (2, 2, 1),
+            (2, 2, 0),
+            (2, 0, 1),
+            (2, 0, 0),
+        ];
+        assert_eq!(part1(&cubes).unwrap(), 52);
+        assert_eq!(part2(&cubes).unwrap(), 42);
+    }
+
+    proptest! {
+        #[test]
+        fn flood_fill_agrees_with_air_pockets(
+            cubes in prop::collection::hash_set((0..8, 0..8, 0..8), 1..200),
+        ) {
+            let cubes: Vec<_> = cubes.into_iter().collect();
+            prop_assert_eq!(part2(&cubes).unwrap(), part2_with_flood_fill(&cubes).unwrap());
+        }
+    }
+}
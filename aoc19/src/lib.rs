@@ -0,0 +1,313 @@
+use std::collections::HashSet;
+use std::str::FromStr;
+
+use aoc_common::{parse_err, Answer, Error, Generator, Knobs, Position, Result, Rng, Solution};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use tracing::{debug, debug_span, trace};
+
+pub struct Day19;
+
+impl Solution for Day19 {
+    type Input = Vec<Blueprint>;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_input(input)
+    }
+
+    fn part1(blueprints: &Self::Input) -> Result<Answer> {
+        part1(&mut blueprints.clone()).map(Answer::from)
+    }
+
+    fn part2(blueprints: &Self::Input) -> Result<Answer> {
+        part2(&mut blueprints.clone()).map(Answer::from)
+    }
+
+    fn generator() -> Option<Generator> {
+        Some(Generator {
+            size: "blueprints",
+            default_size: 30,
+            knobs: &[],
+            generate,
+        })
+    }
+}
+
+type Key = (u16, u16, u16, u16, u16, u16, u16, u16, u16); // 9 u16, with time
+
+pub fn parse_input(input: &str) -> Result<Vec<Blueprint>> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, l)| l.parse().at_line(i + 1))
+        .collect()
+}
+
+pub fn part1(blueprints: &mut [Blueprint]) -> Result<u64> {
+    let result = blueprints_iter(blueprints)
+        .map(|b| b.most_geodes(24) as u64 * b.id as u64)
+        .sum();
+    Ok(result)
+}
+
+pub fn part2(blueprints: &mut [Blueprint]) -> Result<u64> {
+    let l = blueprints.len();
+    let result = blueprints_iter(&mut blueprints[..3.min(l)])
+        .map(|b| b.most_geodes(32) as u64)
+        .product();
+    Ok(result)
+}
+
+/// The blueprints are independent, with the `parallel` feature they are evaluated at the same time.
+#[cfg(not(feature = "parallel"))]
+fn blueprints_iter(blueprints: &mut [Blueprint]) -> impl Iterator<Item = &mut Blueprint> {
+    blueprints.iter_mut()
+}
+
+#[cfg(feature = "parallel")]
+fn blueprints_iter(blueprints: &mut [Blueprint]) -> impl ParallelIterator<Item = &mut Blueprint> {
+    blueprints.par_iter_mut()
+}
+
+#[derive(Clone, Debug)]
+pub struct Blueprint {
+    id: u16,
+    ore_cost: u16,
+    clay_cost: u16,
+    obsidian_cost: (u16, u16),
+    geode_cost: (u16, u16),
+    max_ore: u16,
+    max_clay: u16,
+    max_obsidian: u16,
+    max_geodes: u16,
+}
+
+#[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
+struct State {
+    robots: [u16; 4],
+    goods: [u16; 4],
+}
+
+impl State {
+    fn new() -> Self {
+        State {
+            robots: [1, 0, 0, 0],
+            goods: [0, 0, 0, 0],
+        }
+    }
+
+    fn collect(&mut self) {
+        for (index, count) in self.robots.iter().enumerate() {
+            self.goods[index] += count;
+        }
+    }
+
+    fn key(&self, time: u16) -> Key {
+        (
+            self.robots[0],
+            self.robots[1],
+            self.robots[2],
+            self.robots[3],
+            self.goods[0],
+            self.goods[1],
+            self.goods[2],
+            self.goods[3],
+            time,
+        )
+    }
+}
+
+impl Blueprint {
+    /// The most geodes this blueprint can open in `time_limit` minutes.
+    fn most_geodes(&mut self, time_limit: u16) -> u16 {
+        let _span = debug_span!("blueprint", id = self.id, time_limit).entered();
+        let mut memo = HashSet::new();
+        let geodes = self.execute(0, State::new(), &mut memo, time_limit);
+        debug!(geodes, memo = memo.len(), "search done");
+        geodes
+    }
+
+    fn execute(
+        &mut self,
+        time: u16,
+        mut state: State,
+        memo: &mut HashSet<Key>,
+        time_limit: u16,
+    ) -> u16 {
+        if time == time_limit {
+            self.max_geodes = self.max_geodes.max(state.goods[3]);
+            return state.goods[3];
+        }
+        let mut p_g = state.goods[3];
+        for i in 1..(time_limit - time + 1) {
+            // max geode robot count is time_limit - time
+            p_g += state.robots[3] + i - 1
+        }
+        if p_g <= self.max_geodes {
+            // the recursion goes one minute deeper at a time
+            trace!(
+                depth = time,
+                "pruned, can not beat {} geodes",
+                self.max_geodes
+            );
+            return 0;
+        }
+        state = self.trim_goods(state, time, time_limit);
+        let key = state.key(time);
+        memo.insert(key);
+        let (r1, r2, r3, r4) = (
+            self.can_build_geode_robot(&state),
+            self.can_build_obsidian_robot(&state),
+            self.can_build_clay_robot(&state),
+            self.can_build_ore_robot(&state),
+        ); // dry-run before collect
+        state.collect();
+        let mut new_state = vec![];
+        if r1 {
+            new_state.push(self.build_geode_robot(&state))
+        } else {
+            if r2 && state.robots[2] < self.max_obsidian {
+                new_state.push(self.build_obsidian_robot(&state))
+            }
+            if r3 && state.robots[1] < self.max_clay {
+                new_state.push(self.build_clay_robot(&state))
+            }
+            if r4 && state.robots[0] < self.max_ore {
+                new_state.push(self.build_ore_robot(&state))
+            }
+            new_state.push(state);
+        }
+        let r = new_state
+            .into_iter()
+            .map(|s| {
+                let key = s.key(time + 1);
+                if memo.contains(&key) {
+                    trace!(depth = time + 1, "memo hit");
+                    0
+                } else {
+                    self.execute(time + 1, s, memo, time_limit)
+                }
+            })
+            .max()
+            .unwrap();
+        self.max_geodes = self.max_geodes.max(r);
+        r
+    }
+
+    fn trim_goods(&self, mut state: State, time: u16, time_limit: u16) -> State {
+        state.goods[0] = state.goods[0].min((time_limit - time) * self.max_ore);
+        state.goods[1] = state.goods[1].min((time_limit - time) * self.max_clay);
+        state.goods[2] = state.goods[2].min((time_limit - time) * self.max_obsidian);
+        state
+    }
+
+    fn build_geode_robot(&self, state: &State) -> State {
+        let mut state = *state;
+        state.goods[0] -= self.geode_cost.0;
+        state.goods[2] -= self.geode_cost.1;
+        state.robots[3] += 1;
+        state
+    }
+
+    fn build_obsidian_robot(&self, state: &State) -> State {
+        let mut state = *state;
+        state.goods[0] -= self.obsidian_cost.0;
+        state.goods[1] -= self.obsidian_cost.1;
+        state.robots[2] += 1;
+        state
+    }
+
+    fn build_clay_robot(&self, state: &State) -> State {
+        let mut state = *state;
+        state.goods[0] -= self.clay_cost;
+        state.robots[1] += 1;
+        state
+    }
+
+    fn build_ore_robot(&self, state: &State) -> State {
+        let mut state = *state;
+        state.goods[0] -= self.ore_cost;
+        state.robots[0] += 1;
+        state
+    }
+
+    fn can_build_geode_robot(&self, state: &State) -> bool {
+        state.goods[0] >= self.geode_cost.0 && state.goods[2] >= self.geode_cost.1
+    }
+
+    fn can_build_obsidian_robot(&self, state: &State) -> bool {
+        state.goods[0] >= self.obsidian_cost.0 && state.goods[1] >= self.obsidian_cost.1
+    }
+
+    fn can_build_clay_robot(&self, state: &State) -> bool {
+        state.goods[0] >= self.clay_cost
+    }
+
+    fn can_build_ore_robot(&self, state: &State) -> bool {
+        state.goods[0] >= self.ore_cost
+    }
+}
+
+impl FromStr for Blueprint {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let r: Vec<_> = s
+            .trim()
+            .split([' ', ':'])
+            .filter_map(|w| w.parse::<u16>().ok())
+            .collect();
+        if r.len() != 7 {
+            parse_err!("input is not a valid blueprint: {}", s)
+        } else {
+            Ok(Self {
+                id: r[0],
+                ore_cost: r[1],
+                clay_cost: r[2],
+                obsidian_cost: (r[3], r[4]),
+                geode_cost: (r[5], r[6]),
+                max_ore: r[1].max(r[2]).max(r[3]).max(r[4]),
+                max_clay: r[4],
+                max_obsidian: r[6],
+                max_geodes: 0,
+            })
+        }
+    }
+}
+
+/// The costs are in the ranges of the real input.
+fn generate(rng: &mut Rng, blueprints: usize, _knobs: &Knobs) -> Result<String> {
+    let lines: Vec<String> = (1..=blueprints.max(1))
+        .map(|id| {
+            format!(
+                "Blueprint {id}: Each ore robot costs {} ore. Each clay robot costs {} ore. \
+                 Each obsidian robot costs {} ore and {} clay. \
+                 Each geode robot costs {} ore and {} obsidian.",
+                rng.between(2, 4),
+                rng.between(2, 4),
+                rng.between(2, 4),
+                rng.between(5, 20),
+                rng.between(2, 4),
+                rng.between(7, 20),
+            )
+        })
+        .collect();
+    Ok(lines.join("\n"))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+
+    #[test]
+    fn example_input() {
+        let input = "Blueprint 1: Each ore robot costs 4 ore. Each clay robot costs 2 ore. Each obsidian robot costs 3 ore and 14 clay. Each geode robot costs 2 ore and 7 obsidian.
+        Blueprint 2: Each ore robot costs 2 ore. Each clay robot costs 3 ore. Each obsidian robot costs 3 ore and 8 clay. Each geode robot costs 3 ore and 12 obsidian.";
+        let mut blueprints: Vec<Blueprint> = input.lines().map(|l| l.parse().unwrap()).collect();
+        // assert_eq!(part1(&blueprints[..1]).unwrap(), 9);
+        // assert_eq!(part1(&blueprints[1..]).unwrap(), 24);
+        assert_eq!(part1(&mut blueprints).unwrap(), 33);
+        assert_eq!(part2(&mut blueprints[..1]).unwrap(), 56);
+        assert_eq!(part2(&mut blueprints[1..]).unwrap(), 62);
+    }
+}
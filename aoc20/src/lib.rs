@@ -0,0 +1,220 @@
+use aoc_common::{parse_err, Answer, Generator, Knobs, Position, Result, Rng, Solution, Variant};
+
+pub struct Day20;
+
+impl Solution for Day20 {
+    type Input = Vec<(i64, usize)>;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_input(input)
+    }
+
+    fn part1(nums: &Self::Input) -> Result<Answer> {
+        part1(nums).map(Answer::from)
+    }
+
+    fn part2(nums: &Self::Input) -> Result<Answer> {
+        part2_in_place(nums).map(Answer::from)
+    }
+
+    fn variants() -> Vec<Variant> {
+        vec![Variant {
+            name: "remove and insert",
+            part: 2,
+            solve: |input| part2(&parse_input(input)?).map(Answer::from),
+        }]
+    }
+
+    fn generator() -> Option<Generator> {
+        Some(Generator {
+            size: "numbers",
+            default_size: 5000,
+            knobs: &[],
+            generate,
+        })
+    }
+}
+
+pub fn part1(nums: &[(i64, usize)]) -> Result<i64> {
+    let mut nums = nums.to_vec();
+    mixing(&mut nums);
+    let zero_index = nums.iter().position(|n| n.0 == 0).unwrap();
+    let result = [1000, 2000, 3000].iter().fold(0, |sum, &offset| {
+        sum + nums[(zero_index + offset) % nums.len()].0
+    });
+    Ok(result)
+}
+
+pub fn part2(nums: &[(i64, usize)]) -> Result<i64> {
+    let mut nums: Vec<(i64, usize)> = nums
+        .iter()
+        .cloned()
+        .map(|(n, i)| (n * 811589153, i))
+        .collect();
+    for _ in 0..10 {
+        mixing(&mut nums);
+    }
+    let zero_index = nums.iter().position(|n| n.0 == 0).unwrap();
+    let result = [1000, 2000, 3000].iter().fold(0, |sum, &offset| {
+        sum + nums[(zero_index + offset) % nums.len()].0
+    });
+    Ok(result)
+}
+
+fn mixing(nums: &mut Vec<(i64, usize)>) {
+    let length = nums.len();
+    let mut cur_index = 0;
+    for _ in 0..length {
+        for i in 0..length {
+            if nums[i].1 == cur_index {
+                // never moved
+                let (offset, index) = nums[i];
+                let next = wrap(i as i64, offset, length as i64);
+                nums.remove(i);
+                nums.insert(next, (offset, index));
+                cur_index += 1;
+                break;
+            }
+        }
+    }
+}
+
+pub fn part2_in_place(nums: &[(i64, usize)]) -> Result<i64> {
+    let mut nums: Vec<(i64, usize)> = nums
+        .iter()
+        .cloned()
+        .map(|(n, i)| (n * 811589153, i))
+        .collect();
+    for _ in 0..10 {
+        mixing_in_place(&mut nums);
+    }
+    let zero_index = nums.iter().find(|n| n.0 == 0).unwrap().1;
+    let result = [1000, 2000, 3000].iter().fold(0, |sum, &offset| {
+        sum + nums
+            .iter()
+            .find(|n| n.1 == (zero_index + offset) % nums.len())
+            .unwrap()
+            .0
+    });
+    Ok(result)
+}
+
+fn mixing_in_place(nums: &mut [(i64, usize)]) {
+    let length = nums.len();
+    for i in 0..length {
+        // never moved
+        let (offset, cur) = nums[i];
+        let next = wrap(cur as i64, offset, length as i64);
+        nums[i].1 = next;
+        if next > cur {
+            nums.iter_mut()
+                .enumerate()
+                .filter(|(j, n)| &i != j && n.1 > cur && n.1 <= next)
+                .for_each(|(_, n)| n.1 -= 1);
+        } else {
+            nums.iter_mut()
+                .enumerate()
+                .filter(|(j, n)| &i != j && n.1 >= next && n.1 < cur)
+                .for_each(|(_, n)| n.1 += 1);
+        }
+    }
+}
+
+fn wrap(cur: i64, offset: i64, length: i64) -> usize {
+    // let next = cur + offset;
+    // if next >= 0 && next < length {
+    //     next as usize
+    // } else if next < 0 {
+    //     (next % (length - 1) + length - 1) as usize
+    // } else {
+    //     (next % (length - 1)) as usize
+    // }
+    // or
+    (cur + offset).rem_euclid(length - 1) as usize
+}
+
+/// Even multiplied by the decryption key, the sum of three numbers fits in an `i64`.
+const MAX_NUMBER: i64 = 1 << 31;
+
+pub fn parse_input(input: &str) -> Result<Vec<(i64, usize)>> {
+    // there are duplicate number
+    let mut nums = Vec::new();
+    for (i, l) in input.lines().enumerate() {
+        let n: i64 = l.trim().parse().at_line(i + 1)?;
+        if n.abs() > MAX_NUMBER {
+            return parse_err!("{n} is too large").at_line(i + 1);
+        }
+        nums.push((n, i));
+    }
+    if nums.len() < 2 {
+        return parse_err!("there are less than two numbers to mix");
+    }
+    if nums.iter().filter(|n| n.0 == 0).count() != 1 {
+        return parse_err!("there should be exactly one 0");
+    }
+    Ok(nums)
+}
+
+/// Numbers like in the real input, with a single 0 among them.
+fn generate(rng: &mut Rng, numbers: usize, _knobs: &Knobs) -> Result<String> {
+    let count = numbers.max(2);
+    let zero = rng.index(count);
+    let nums: Vec<String> = (0..count)
+        .map(|i| {
+            if i == zero {
+                return 0;
+            }
+            let n = rng.between(1, 10000);
+            if rng.chance(0.5) {
+                -n
+            } else {
+                n
+            }
+        })
+        .map(|n| n.to_string())
+        .collect();
+    Ok(nums.join("\n"))
+}
+
+#[test]
+fn example_input() {
+    let input = "1
+    2
+    -3
+    3
+    -2
+    0
+    4";
+    let nums = parse_input(input).unwrap();
+    assert_eq!(3, part1(&nums).unwrap());
+    assert_eq!(1623178306, part2(&nums).unwrap());
+    assert_eq!(1623178306, part2_in_place(&nums).unwrap());
+
+    // let mut t = vec![(3, 0), (1, 1), (0, 2)];
+    // mixing(&mut t);
+    // assert_eq!(vec![(3, 0), (1, 1), (0, 2)], t);
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    proptest! {
+        #[test]
+        fn in_place_agrees_with_remove_and_insert(
+            nums in prop::collection::vec((1i64..10000, any::<bool>()), 1..100),
+            zero in any::<prop::sample::Index>(),
+        ) {
+            let mut nums: Vec<i64> = nums
+                .into_iter()
+                .map(|(n, negative)| if negative { -n } else { n })
+                .collect();
+            nums.insert(zero.index(nums.len() + 1), 0);
+            let input = nums.iter().map(|n| n.to_string()).collect::<Vec<_>>().join("\n");
+            let nums = parse_input(&input).unwrap();
+            prop_assert_eq!(part2(&nums).unwrap(), part2_in_place(&nums).unwrap());
+        }
+    }
+}
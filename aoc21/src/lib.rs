@@ -0,0 +1,654 @@
+use std::collections::{HashMap, HashSet};
+
+use aoc_common::{
+    parse_err, unsolvable, Answer, Generator, Knobs, Position, Result, Rng, Solution,
+};
+use tracing::{debug, debug_span, trace};
+
+pub struct Day21;
+
+impl Solution for Day21 {
+    type Input = (Vec<Monkey>, HashMap<String, Integer>);
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_input(input)
+    }
+
+    fn part1((monkeys, index): &Self::Input) -> Result<Answer> {
+        part1(monkeys, index).map(Answer::from)
+    }
+
+    fn part2((monkeys, index): &Self::Input) -> Result<Answer> {
+        part2(monkeys, index).map(Answer::from)
+    }
+
+    fn generator() -> Option<Generator> {
+        Some(Generator {
+            size: "monkeys",
+            default_size: 2400,
+            knobs: &[],
+            generate,
+        })
+    }
+}
+
+pub type Integer = i64;
+
+pub fn part1(monkeys: &[Monkey], index: &HashMap<String, Integer>) -> Result<Integer> {
+    let r = dfs(monkeys, &monkey_id(index, "root")?, &mut HashMap::new(), 1)
+        .unwrap()
+        .unwrap();
+
+    Ok(r)
+}
+
+pub fn part2(monkeys: &[Monkey], index: &HashMap<String, Integer>) -> Result<Integer> {
+    let root_id = monkey_id(index, "root")?;
+    monkey_id(index, "humn")?;
+    let (m1, m2) = match &monkeys[root_id as usize].yell {
+        Yell::Number(_) => return unsolvable!("not a valid root: {:?}", monkeys[root_id as usize]),
+        Yell::Operation((m1, _, m2)) => (*m1, *m2),
+    };
+
+    let _span = debug_span!("part2", monkeys = monkeys.len()).entered();
+    let mut memo = HashMap::new();
+    dfs(monkeys, &root_id, &mut memo, 2);
+    debug!(memo = memo.len(), "formulas built");
+    let f1 = memo.get(&m1).unwrap();
+    let f2 = memo.get(&m2).unwrap();
+    let humn = solve(f1, f2)?;
+
+    // let &humn_id = index.get("humn").unwrap();
+    // let mut humn = monkeys[humn_id as usize].yell.unwrap().unwrap();
+    // let base1 = f1.calc(humn);
+    // let base2 = f2.calc(humn);
+    // let (mut left, mut right) = (0, 0);
+    // loop {
+    //     let r1 = f1.calc(humn);
+    //     let r2 = f2.calc(humn);
+    //     if r1 == r2 {
+    //         break;
+    //     } else if r1 <= base1 && r1 > base2 {
+    //         humn *= 2
+    //     } else {
+    //         left = humn / 2;
+    //         right = humn;
+    //         break;
+    //     }
+    // }
+    // let mut mid = (left + right) / 2;
+    // while mid < right {
+    //     if f1.calc(mid) == f2.calc(mid) {
+    //         humn = mid;
+    //         break;
+    //     } else if f1.calc(mid) < f2.calc(mid) {
+    //         right = mid
+    //     } else {
+    //         left = mid
+    //     }
+    //     mid = (left + right) / 2;
+    // }
+
+    Ok(humn)
+}
+
+fn monkey_id(index: &HashMap<String, Integer>, name: &str) -> Result<Integer> {
+    match index.get(name) {
+        Some(&id) => Ok(id),
+        None => unsolvable!("there is no monkey {}", name),
+    }
+}
+
+fn dfs(
+    monkeys: &[Monkey],
+    root: &Integer,
+    memo: &mut HashMap<Integer, Formula>,
+    part: u8,
+) -> Formula {
+    // no cycle
+    if part == 2 && monkeys[*root as usize].name == "humn" {
+        return Formula::List(vec![]);
+    }
+    if let Some(r) = memo.get(root) {
+        trace!(monkey = monkeys[*root as usize].name, "memo hit");
+        return r.clone();
+    }
+    let yell = &monkeys[*root as usize].yell;
+    match yell {
+        Yell::Number(n) => {
+            memo.insert(*root, Formula::Number(*n));
+            Formula::Number(*n)
+        }
+        Yell::Operation((m1, op, m2)) => {
+            let op1 = dfs(monkeys, m1, memo, part);
+            let op2 = dfs(monkeys, m2, memo, part);
+            let r = match (op1, op2) {
+                (Formula::Number(op1), Formula::Number(op2)) => Formula::Number(calc(op, op1, op2)),
+                (Formula::Number(op1), Formula::List(f2)) => Formula::List(vec![
+                    Formula::Number(op1),
+                    Formula::Operation(*op),
+                    Formula::List(f2),
+                ]),
+                (Formula::List(f1), Formula::Number(op2)) => Formula::List(vec![
+                    Formula::List(f1),
+                    Formula::Operation(*op),
+                    Formula::Number(op2),
+                ]),
+                (Formula::List(f1), Formula::List(f2)) => Formula::List(vec![
+                    Formula::List(f1),
+                    Formula::Operation(*op),
+                    Formula::List(f2),
+                ]),
+                (Formula::Operation(_), _) => unreachable!(),
+                (_, Formula::Operation(_)) => unreachable!(),
+            };
+            memo.insert(*root, r.clone());
+            r
+        }
+    }
+}
+
+fn solve(f1: &Formula, f2: &Formula) -> Result<Integer> {
+    trace!("solve {} = {}", f1.pretty(), f2.pretty());
+    match (f1, f2) {
+        (Formula::List(v), Formula::Number(op1)) => {
+            if v.is_empty() {
+                return Ok(*op1);
+            }
+            let op = v[1].get_op().unwrap();
+            let mut op2 = None;
+            let mut next_f = None;
+            let mut op_index = 0;
+            for (i, f) in v.iter().enumerate() {
+                match f {
+                    Formula::Number(n) => {
+                        op2 = {
+                            op_index = i;
+                            Some(*n)
+                        }
+                    }
+                    Formula::Operation(_) => (),
+                    Formula::List(_) => next_f = Some(f),
+                }
+            }
+            let mut op1 = *op1;
+            if let Some(op2) = op2 {
+                if op == '*' || op == '+' || op_index == 2 {
+                    op1 = calc_rev(&op, op1, op2)
+                } else if op_index == 0 {
+                    if op == '/' {
+                        op1 = op2 / op1
+                    } else if op == '-' {
+                        op1 = op2 - op1
+                    } else {
+                        unreachable!()
+                    }
+                } else {
+                    unreachable!()
+                }
+                if let Some(f) = next_f {
+                    return solve(f, &Formula::Number(op1));
+                }
+            }
+            unsolvable!("can not solve {}={}", f1.pretty(), f2.pretty())
+        }
+        (Formula::Number(_), Formula::List(_)) => solve(f2, f1),
+        (Formula::List(_), Formula::List(_)) => {
+            unsolvable!("can not solve {}={}", f1.pretty(), f2.pretty())
+        }
+        (Formula::Number(_), Formula::Number(_)) => {
+            unsolvable!("can not solve {}={}", f1.pretty(), f2.pretty())
+        }
+        (Formula::Operation(_), _) => unsolvable!("can not solve {}={}", f1.pretty(), f2.pretty()),
+        (_, Formula::Operation(_)) => unsolvable!("can not solve {}={}", f1.pretty(), f2.pretty()),
+    }
+}
+
+fn calc(op: &char, op1: Integer, op2: Integer) -> Integer {
+    match op {
+        '+' => op1 + op2,
+        '-' => op1 - op2,
+        '*' => op1 * op2,
+        '/' => op1 / op2,
+        _ => unreachable!(),
+    }
+}
+
+fn calc_rev(op: &char, op1: Integer, op2: Integer) -> Integer {
+    match op {
+        '+' => op1 - op2,
+        '-' => op1 + op2,
+        '*' => op1 / op2,
+        '/' => op1 * op2,
+        _ => unreachable!(),
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Formula {
+    Number(Integer),
+    Operation(char),
+    List(Vec<Formula>),
+}
+
+impl Formula {
+    fn unwrap(&self) -> Option<Integer> {
+        match self {
+            Formula::Number(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    fn get_op(&self) -> Option<char> {
+        match self {
+            Formula::Number(_) => None,
+            Formula::Operation(c) => Some(*c),
+            Formula::List(_) => None,
+        }
+    }
+
+    fn pretty(&self) -> String {
+        let mut s = String::new();
+        match self {
+            Formula::Number(n) => s.push_str(&format!("{n}")),
+            Formula::Operation(op) => s.push_str(&format!(" {op} ")),
+            Formula::List(v) => {
+                if v.is_empty() {
+                    s.push_str("humn");
+                }
+                for f in v {
+                    match f {
+                        Formula::Number(_) | Formula::Operation(_) => s.push_str(&f.pretty()),
+                        Formula::List(_) => {
+                            s.push('(');
+                            s.push_str(&f.pretty());
+                            s.push(')');
+                        }
+                    }
+                }
+            }
+        }
+        s
+    }
+
+    fn _simplify(self) -> Formula {
+        // not working
+        match &self {
+            Formula::Number(_) => self,
+            Formula::Operation(_) => self,
+            Formula::List(v) => {
+                let l = v.len();
+                if l == 0 {
+                    self
+                } else if l == 3 {
+                    let f1 = v[0].clone();
+                    let f2 = v[2].clone();
+                    let op = v[1].get_op().unwrap();
+                    if op == '+' || op == '-' {
+                        return self;
+                    }
+                    match (f1, f2) {
+                        (Formula::Number(op1), Formula::Number(op2)) => {
+                            Formula::Number(calc(&op, op1, op2))
+                        }
+                        (Formula::Number(op1), Formula::List(inner_v)) => Formula::List(
+                            inner_v
+                                .iter()
+                                .cloned()
+                                .map(move |f| match f {
+                                    Formula::Number(op2) => Formula::Number(calc(&op, op1, op2)),
+                                    Formula::Operation(_) => f.clone(),
+                                    Formula::List(inner_v_v) => Formula::List(vec![
+                                        Formula::Number(op1),
+                                        Formula::Operation(op),
+                                        Formula::List(inner_v_v),
+                                    ]),
+                                })
+                                .collect(),
+                        )
+                        ._simplify(),
+                        (Formula::List(inner_v), Formula::Number(op2)) => Formula::List(
+                            inner_v
+                                .iter()
+                                .cloned()
+                                .map(move |f| match f {
+                                    Formula::Number(op1) => Formula::Number(calc(&op, op1, op2)),
+                                    Formula::Operation(_) => f.clone(),
+                                    Formula::List(inner_v_v) => Formula::List(vec![
+                                        Formula::List(inner_v_v),
+                                        Formula::Operation(op),
+                                        Formula::Number(op2),
+                                    ]),
+                                })
+                                .collect(),
+                        )
+                        ._simplify(),
+                        (Formula::List(v1), Formula::List(v2)) => Formula::List(vec![
+                            Formula::List(v1)._simplify(),
+                            Formula::Operation(op),
+                            Formula::List(v2)._simplify(),
+                        ]),
+                        (Formula::Operation(_), _) => unreachable!(),
+                        (_, Formula::Operation(_)) => unreachable!(),
+                    }
+                } else {
+                    unreachable!()
+                }
+            }
+        }
+    }
+
+    #[allow(dead_code)]
+    fn calc(&self, unknow: Integer) -> Integer {
+        match self {
+            Formula::Number(n) => *n,
+            Formula::Operation(_) => unreachable!(),
+            Formula::List(v) => {
+                if v.is_empty() {
+                    return unknow;
+                }
+                let op1 = v[0].calc(unknow);
+                let op2 = v[2].calc(unknow);
+                let op = v[1].get_op().unwrap();
+                calc(&op, op1, op2)
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+enum Yell {
+    Number(Integer),
+    Operation((Integer, char, Integer)),
+}
+
+impl Yell {
+    #[allow(dead_code)]
+    fn unwrap(&self) -> Option<Integer> {
+        match self {
+            Yell::Number(n) => Some(*n),
+            Yell::Operation(_) => None,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Monkey {
+    name: String,
+    yell: Yell,
+}
+
+pub fn parse_input(input: &str) -> Result<(Vec<Monkey>, HashMap<String, Integer>)> {
+    fn get_moneky_id(
+        index: &mut HashMap<String, Integer>,
+        monkeys: &mut Vec<Option<Monkey>>,
+        max_id: &mut Integer,
+        name: &str,
+    ) -> Integer {
+        if let Some(&id) = index.get(name) {
+            id
+        } else {
+            index.insert(name.to_string(), *max_id);
+            monkeys.push(None);
+            *max_id += 1;
+            *max_id - 1
+        }
+    }
+
+    let mut index = HashMap::new();
+    let mut max_id = 0;
+    let mut result = vec![];
+    for (i, line) in input.lines().enumerate() {
+        if let Some((name, yell)) = line.trim().split_once(": ") {
+            let id = get_moneky_id(&mut index, &mut result, &mut max_id, name);
+            if result[id as usize].is_some() {
+                return parse_err!("monkey {} yells twice", name).at_line(i + 1);
+            }
+            let yell = yell.split(' ').collect::<Vec<_>>();
+            if yell.len() == 1 {
+                result[id as usize] = Some(Monkey {
+                    name: name.to_string(),
+                    yell: Yell::Number(yell[0].parse().at_line(i + 1)?),
+                });
+            } else if yell.len() == 3 {
+                let op = match yell[1] {
+                    "+" => '+',
+                    "-" => '-',
+                    "*" => '*',
+                    "/" => '/',
+                    _ => return parse_err!("not a valid monkey yell: {}", line).at_line(i + 1),
+                };
+                let id1 = get_moneky_id(&mut index, &mut result, &mut max_id, yell[0]);
+                let id2 = get_moneky_id(&mut index, &mut result, &mut max_id, yell[2]);
+                result[id as usize] = Some(Monkey {
+                    name: name.to_string(),
+                    yell: Yell::Operation((id1, op, id2)),
+                });
+            } else {
+                return parse_err!("not a valid monkey yell: {}", line).at_line(i + 1);
+            }
+        } else {
+            return parse_err!("not a valid monkey: {}", line).at_line(i + 1);
+        }
+    }
+    if let Some((name, _)) = index.iter().find(|(_, &id)| result[id as usize].is_none()) {
+        return parse_err!("monkey {} never yells", name);
+    }
+    if !index.contains_key("root") {
+        return parse_err!("there is no root monkey");
+    }
+    let result: Vec<Monkey> = result.into_iter().flatten().collect();
+    if let Some(id) = find_cycle(&result) {
+        return parse_err!("monkey {} waits for its own number", result[id].name);
+    }
+    Ok((result, index))
+}
+
+/// Returns a monkey that ends up waiting for itself, with an iterative depth first search since
+/// the chains of monkeys are long.
+fn find_cycle(monkeys: &[Monkey]) -> Option<usize> {
+    // 0 not visited, 1 on the current path, 2 done
+    let mut state = vec![0u8; monkeys.len()];
+    for start in 0..monkeys.len() {
+        if state[start] != 0 {
+            continue;
+        }
+        let mut stack = vec![(start, false)];
+        while let Some((id, done)) = stack.pop() {
+            if done {
+                state[id] = 2;
+                continue;
+            }
+            match state[id] {
+                1 => return Some(id),
+                2 => continue,
+                _ => (),
+            }
+            state[id] = 1;
+            stack.push((id, true));
+            if let Yell::Operation((id1, _, id2)) = monkeys[id].yell {
+                for next in [id1 as usize, id2 as usize] {
+                    match state[next] {
+                        0 => stack.push((next, false)),
+                        1 => return Some(next),
+                        _ => (),
+                    }
+                }
+            }
+        }
+    }
+    None
+}
+
+/// No number yelled gets above this, so nothing overflows.
+const GENERATED_LIMIT: Integer = 1 << 40;
+
+/// The monkeys of a generated input, by name, with what they yell.
+struct Troop {
+    monkeys: Vec<(String, String)>,
+    names: HashSet<String>,
+}
+
+impl Troop {
+    fn add(&mut self, rng: &mut Rng, yell: String) -> String {
+        let name = loop {
+            let name: String = (0..4)
+                .map(|_| (b'a' + rng.below(26) as u8) as char)
+                .collect();
+            if self.names.insert(name.clone()) {
+                break name;
+            }
+        };
+        self.monkeys.push((name.clone(), yell));
+        name
+    }
+
+    /// A tree of `size` monkeys, odd, without humn. Returns the monkey at the top and its number.
+    fn tree(&mut self, rng: &mut Rng, size: usize) -> (String, Integer) {
+        if size == 1 {
+            let n = rng.between(1, 20);
+            return (self.add(rng, n.to_string()), n);
+        }
+        let left = rng.index(size / 2) * 2 + 1;
+        let (mut m1, mut n1) = self.tree(rng, left);
+        let (mut m2, mut n2) = self.tree(rng, size - 1 - left);
+        if n1 < n2 || (n1 == n2 && rng.chance(0.5)) {
+            (m1, n1, m2, n2) = (m2, n2, m1, n1);
+        }
+        // n1 >= n2, so one of the subtraction and the division always works
+        let mut ops = vec![];
+        if n1 + n2 <= GENERATED_LIMIT {
+            ops.push(('+', n1 + n2));
+        }
+        if n1 > n2 {
+            ops.push(('-', n1 - n2));
+        }
+        if n1.checked_mul(n2).is_some_and(|n| n <= GENERATED_LIMIT) {
+            ops.push(('*', n1 * n2));
+        }
+        if n1 % n2 == 0 {
+            ops.push(('/', n1 / n2));
+        }
+        let &(op, n) = rng.choose(&ops);
+        if op != '-' && op != '/' && rng.chance(0.5) {
+            (m1, m2) = (m2, m1);
+        }
+        (self.add(rng, format!("{m1} {op} {m2}")), n)
+    }
+}
+
+/// The monkeys on the way from humn to root each wait for another tree of monkeys. The numbers on
+/// that way are followed both for the number humn yells and for the answer of part 2, and only
+/// the operations that keep both whole are used.
+fn generate(rng: &mut Rng, monkeys: usize, _knobs: &Knobs) -> Result<String> {
+    // every monkey but the ones yelling a number waits for two others
+    let count = monkeys.max(7) | 1;
+    let mut troop = Troop {
+        monkeys: vec![],
+        names: HashSet::from(["root".to_string(), "humn".to_string()]),
+    };
+    let yelled = rng.between(1, 4000);
+    let answer = loop {
+        let answer = rng.between(1, 4000);
+        if answer != yelled {
+            break answer;
+        }
+    };
+    troop.monkeys.push(("humn".to_string(), yelled.to_string()));
+
+    // root, humn and the two monkeys balancing the other side of root
+    let other_size = ((count - 4) / 2) | 1;
+    let mut left = count - 4 - other_size;
+    let (mut top, mut at_answer, mut at_yelled) = ("humn".to_string(), answer, yelled);
+    while left > 0 {
+        let size = rng.index(left.min(60) / 2) * 2 + 1;
+        let mark = troop.monkeys.len();
+        let (other, n) = troop.tree(rng, size);
+        let mut ops = vec![];
+        let fits = |a: Integer, b: Integer| a > 0 && b > 0 && a.max(b) <= GENERATED_LIMIT;
+        if fits(at_answer + n, at_yelled + n) {
+            ops.push(("+", at_answer + n, at_yelled + n));
+        }
+        if fits(at_answer - n, at_yelled - n) {
+            ops.push(("-", at_answer - n, at_yelled - n));
+        }
+        if fits(n - at_answer, n - at_yelled) {
+            ops.push(("- from", n - at_answer, n - at_yelled));
+        }
+        if at_answer.max(at_yelled) <= GENERATED_LIMIT / n {
+            ops.push(("*", at_answer * n, at_yelled * n));
+        }
+        if at_answer % n == 0 && at_yelled % n == 0 {
+            ops.push(("/", at_answer / n, at_yelled / n));
+        }
+        if ops.is_empty() {
+            for (name, _) in troop.monkeys.drain(mark..) {
+                troop.names.remove(&name);
+            }
+            continue;
+        }
+        let &(op, a, y) = rng.choose(&ops);
+        let yell = match op {
+            "- from" => format!("{other} - {top}"),
+            "+" | "*" if rng.chance(0.5) => format!("{other} {op} {top}"),
+            _ => format!("{top} {op} {other}"),
+        };
+        top = troop.add(rng, yell);
+        (at_answer, at_yelled) = (a, y);
+        left -= size + 1;
+    }
+
+    // the other side of root yells the number humn's side gets to with the answer
+    let (other, n) = troop.tree(rng, other_size);
+    let balance = if n <= at_answer {
+        let rest = troop.add(rng, (at_answer - n).to_string());
+        format!("{other} + {rest}")
+    } else {
+        let rest = troop.add(rng, (n - at_answer).to_string());
+        format!("{other} - {rest}")
+    };
+    let balance = troop.add(rng, balance);
+    let root = if rng.chance(0.5) {
+        format!("{top} + {balance}")
+    } else {
+        format!("{balance} + {top}")
+    };
+    troop.monkeys.push(("root".to_string(), root));
+
+    let mut lines: Vec<String> = troop
+        .monkeys
+        .into_iter()
+        .map(|(name, yell)| format!("{name}: {yell}"))
+        .collect();
+    rng.shuffle(&mut lines);
+    Ok(lines.join("\n"))
+}
+
+#[test]
+fn example_input() {
+    let input = "root: pppw + sjmn
+    dbpl: 5
+    cczh: sllz + lgvd
+    zczc: 2
+    ptdq: humn - dvpt
+    dvpt: 3
+    lfqf: 4
+    humn: 5
+    ljgn: 2
+    sjmn: drzm * dbpl
+    sllz: 4
+    pppw: cczh / lfqf
+    lgvd: ljgn * ptdq
+    drzm: hmdt - zczc
+    hmdt: 32";
+    let (monkeys, index) = parse_input(input).unwrap();
+    assert_eq!(monkeys.len(), input.lines().count());
+    assert_eq!(152, part1(&monkeys, &index).unwrap());
+    assert_eq!(301, part2(&monkeys, &index).unwrap());
+
+    assert!(parse_input("").is_err());
+    assert!(parse_input("wbky: 33").is_err());
+    let (monkeys, index) = parse_input("root: aaaa + bbbb\naaaa: 1\nbbbb: 2").unwrap();
+    assert_eq!(3, part1(&monkeys, &index).unwrap());
+    assert!(part2(&monkeys, &index).is_err());
+}
@@ -0,0 +1,582 @@
+use std::collections::{HashMap, HashSet};
+
+use aoc_common::{
+    invariant, parse_err, unsolvable, Animation, Answer, Generator, Knobs, Position, Result, Rng,
+    Screen, Solution,
+};
+use aoc_grid::Grid;
+use tracing::{debug, debug_span, trace};
+
+pub struct Day22;
+
+impl Solution for Day22 {
+    type Input = (Map, Vec<Movement>);
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        parse_input(input)
+    }
+
+    fn part1((map, path): &Self::Input) -> Result<Answer> {
+        part1(map, path).map(Answer::from)
+    }
+
+    fn part2((map, path): &Self::Input) -> Result<Answer> {
+        part2(&mut map.clone(), path).map(Answer::from)
+    }
+
+    fn animations() -> Vec<Animation> {
+        vec![
+            Animation {
+                name: "board walk",
+                part: 1,
+                animate: |input, screen| animate(input, 1, screen),
+            },
+            Animation {
+                name: "cube walk",
+                part: 2,
+                animate: |input, screen| animate(input, 2, screen),
+            },
+        ]
+    }
+
+    fn generator() -> Option<Generator> {
+        Some(Generator {
+            size: "turns of the path",
+            default_size: 2000,
+            knobs: &[],
+            generate,
+        })
+    }
+}
+
+/// Shows every step of the walk, leaving the facings on the tiles behind like the puzzle does.
+fn animate(input: &str, part: i32, screen: &mut dyn Screen) -> Result<()> {
+    let (mut map, path) = parse_input(input)?;
+    if part == 2 {
+        map.fold_cube()?;
+    }
+    let mut state = State::new(map.top_left_open_tile()?, 0);
+    let mut trail = HashMap::from([(state.coord(), state.facing)]);
+    state.follow_the_path(&map, &path, part, &mut |state| {
+        trail.insert(state.coord(), state.facing);
+        screen.show(&map.draw(&trail))
+    })
+}
+
+type Coord = (i32, i32);
+
+pub fn part1(map: &Map, path: &[Movement]) -> Result<i32> {
+    let mut state = State::new(map.top_left_open_tile()?, 0);
+    state.follow_the_path(map, path, 1, &mut |_| true)?;
+    let password = state.password();
+
+    Ok(password)
+}
+
+pub fn part2(map: &mut Map, path: &[Movement]) -> Result<i32> {
+    let mut state = State::new(map.top_left_open_tile()?, 0);
+    map.fold_cube()?;
+    state.follow_the_path(map, path, 2, &mut |_| true)?;
+    let password = state.password();
+
+    Ok(password)
+}
+
+#[derive(Debug, Clone)]
+struct State {
+    row: i32,
+    column: i32,
+    facing: i32,
+}
+
+impl State {
+    fn new(coord: Coord, facing: i32) -> Self {
+        Self {
+            row: coord.0,
+            column: coord.1,
+            facing,
+        }
+    }
+
+    fn coord(&self) -> Coord {
+        (self.row, self.column)
+    }
+
+    fn move_with_facing(&self) -> fn(Coord) -> Coord {
+        match self.facing {
+            0 => |(x, y)| (x, y + 1),
+            1 => |(x, y)| (x + 1, y),
+            2 => |(x, y)| (x, y - 1),
+            3 => |(x, y)| (x - 1, y),
+            _ => unreachable!("facing: {} not possible", self.facing),
+        }
+    }
+
+    // facing
+    // > right 0
+    // v down 1
+    // < left 2
+    // ^ up 3
+    //
+    // `on_step` is called after every step and turn, the walk stops when it returns false
+    fn follow_the_path(
+        &mut self,
+        map: &Map,
+        path: &[Movement],
+        part: i32,
+        on_step: &mut dyn FnMut(&State) -> bool,
+    ) -> Result<()> {
+        let _span = debug_span!("walk", part, moves = path.len()).entered();
+        for m in path {
+            let go_on = match m {
+                Movement::S(_) => self.go_stright(m, map, part, on_step)?,
+                Movement::L | Movement::R => {
+                    self.turn(m);
+                    on_step(self)
+                }
+            };
+            if !go_on {
+                return Ok(());
+            }
+        }
+        debug!(
+            row = self.row,
+            column = self.column,
+            facing = self.facing,
+            "walk done"
+        );
+        Ok(())
+    }
+
+    fn turn(&mut self, m: &Movement) {
+        let mut facing = self.facing;
+        match m {
+            Movement::S(_) => unreachable!("Not a turn"),
+            Movement::L => {
+                facing -= 1;
+                if facing == -1 {
+                    facing = 3
+                }
+            }
+            Movement::R => {
+                facing += 1;
+                if facing == 4 {
+                    facing = 0
+                }
+            }
+        }
+        self.facing = facing
+    }
+
+    fn go_stright(
+        &mut self,
+        m: &Movement,
+        map: &Map,
+        part: i32,
+        on_step: &mut dyn FnMut(&State) -> bool,
+    ) -> Result<bool> {
+        let mut real_path: Vec<Coord> = vec![];
+        match m {
+            Movement::S(step) => {
+                let f = self.move_with_facing();
+                if part == 1 {
+                    let mut coord = self.coord();
+                    let mut cur_step = 0;
+                    while cur_step < *step {
+                        coord = f(coord);
+                        coord = map.part1_wrap(&coord); // part one wrap
+                        if let Some(t) = map.get(&coord) {
+                            cur_step += 1;
+                            match t {
+                                Tile::Open => {
+                                    real_path.push(coord);
+                                    (self.row, self.column) = coord;
+                                    if !on_step(self) {
+                                        return Ok(false);
+                                    }
+                                }
+                                Tile::Wall => {
+                                    trace!(row = coord.0, column = coord.1, "hit a wall");
+                                    break;
+                                }
+                            }
+                        } else {
+                            continue;
+                        }
+                    }
+                } else if part == 2 {
+                    return self.part2_go_stright(map, *step, on_step);
+                }
+            }
+            Movement::L | Movement::R => {
+                unreachable!("This movement is a turn, can not go stright")
+            }
+        }
+        Ok(true)
+    }
+
+    fn password(&self) -> i32 {
+        (self.row + 1) * 1000 + (self.column + 1) * 4 + self.facing
+    }
+
+    fn part2_go_stright(
+        &mut self,
+        map: &Map,
+        step: i32,
+        on_step: &mut dyn FnMut(&State) -> bool,
+    ) -> Result<bool> {
+        let mut cur_step = 0;
+        while cur_step < step {
+            let mut coord = self.move_with_facing()(self.coord());
+            let mut facing = self.facing;
+            if map.get(&coord).is_none() {
+                // on the edge
+                (coord, facing) = map.wrap(self)?;
+                trace!(from = ?self.coord(), to = ?coord, facing, "wrapped");
+            }
+            match map.get(&coord) {
+                Some(Tile::Open) => {
+                    (self.row, self.column) = coord;
+                    self.facing = facing;
+                    cur_step += 1;
+                }
+                Some(Tile::Wall) => break,
+                None => return invariant!("wrapped out of the board at {coord:?}"),
+            }
+            if !on_step(self) {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+}
+
+type Vector = [i32; 3];
+
+/// How a face of the board lies on the cube: the direction it faces out, and the directions its
+/// columns and rows go along.
+#[derive(Debug, Clone, Copy)]
+struct Face {
+    normal: Vector,
+    right: Vector,
+    down: Vector,
+}
+
+impl Face {
+    /// The direction on the cube of `facing` on this face.
+    fn towards(&self, facing: i32) -> Vector {
+        match facing {
+            0 => self.right,
+            1 => self.down,
+            2 => self.right.map(|v| -v),
+            _ => self.down.map(|v| -v),
+        }
+    }
+
+    /// The face next to this one on the board in `facing`, once folded along their edge.
+    fn fold(&self, facing: i32) -> Face {
+        let normal = self.towards(facing);
+        let back = self.normal.map(|v| -v);
+        match facing {
+            0 => Face {
+                normal,
+                right: back,
+                down: self.down,
+            },
+            1 => Face {
+                normal,
+                right: self.right,
+                down: back,
+            },
+            2 => Face {
+                normal,
+                right: self.normal,
+                down: self.down,
+            },
+            _ => Face {
+                normal,
+                right: self.right,
+                down: self.normal,
+            },
+        }
+    }
+}
+
+fn dot(v1: Vector, v2: Vector) -> i32 {
+    v1.iter().zip(v2).map(|(a, b)| a * b).sum()
+}
+
+#[derive(Debug, Clone)]
+pub struct Map {
+    /// `None` is outside of the board.
+    map: Grid<Option<Tile>>,
+    cube_size: i32,
+    /// The faces of the folded cube, by row and column of faces on the board.
+    faces: HashMap<Coord, Face>,
+}
+
+impl Map {
+    fn get(&self, k: &Coord) -> Option<&Tile> {
+        self.map
+            .get_signed((k.0 as isize, k.1 as isize))
+            .and_then(Option::as_ref)
+    }
+
+    fn part1_wrap(&self, c: &Coord) -> Coord {
+        (
+            c.0.rem_euclid(self.map.height() as i32),
+            c.1.rem_euclid(self.map.width() as i32),
+        )
+    }
+
+    /// Folds the board into a cube, whatever the layout of its faces.
+    fn fold_cube(&mut self) -> Result<()> {
+        let tiles = self.map.iter().filter(|(_, t)| t.is_some()).count();
+        let size = ((tiles / 6) as f64).sqrt() as i32;
+        if size == 0 || (6 * size * size) as usize != tiles {
+            return unsolvable!("{tiles} tiles are not the faces of a cube");
+        }
+        let first = (0, self.top_left_open_tile()?.1 / size);
+        let mut faces = HashMap::from([(
+            first,
+            Face {
+                normal: [0, 0, 1],
+                right: [1, 0, 0],
+                down: [0, 1, 0],
+            },
+        )]);
+        let mut queue = vec![first];
+        while let Some((row, column)) = queue.pop() {
+            let face = faces[&(row, column)];
+            let next = [
+                (row, column + 1),
+                (row + 1, column),
+                (row, column - 1),
+                (row - 1, column),
+            ];
+            for (facing, next) in next.into_iter().enumerate() {
+                if !faces.contains_key(&next) && self.get(&(next.0 * size, next.1 * size)).is_some()
+                {
+                    faces.insert(next, face.fold(facing as i32));
+                    queue.push(next);
+                }
+            }
+        }
+        let covered = faces.keys().all(|&(row, column)| {
+            (0..size * size).all(|i| {
+                self.get(&(row * size + i / size, column * size + i % size))
+                    .is_some()
+            })
+        });
+        let normals: HashSet<Vector> = faces.values().map(|f| f.normal).collect();
+        if faces.len() != 6 || !covered || normals.len() != 6 {
+            return unsolvable!("unsupported cube layout");
+        }
+        debug!(size, "cube folded");
+        self.cube_size = size;
+        self.faces = faces;
+        Ok(())
+    }
+
+    /// The tile and the facing after going over the edge of the cube `state` stands on.
+    fn wrap(&self, state: &State) -> Result<(Coord, i32)> {
+        let size = self.cube_size;
+        if size == 0 {
+            return invariant!("the board is not folded into a cube");
+        }
+        let (row, column) = state.coord();
+        let Some(face) = self.faces.get(&(row / size, column / size)) else {
+            return invariant!("{:?} is on no face of the cube", state.coord());
+        };
+        // the centers of the tiles on a cube of side `2 * size` around the origin, so that they
+        // are all integers
+        let (i, j) = (row % size, column % size);
+        let center: Vector = std::array::from_fn(|k| {
+            face.normal[k] * size
+                + face.right[k] * (2 * j + 1 - size)
+                + face.down[k] * (2 * i + 1 - size)
+        });
+        let towards = face.towards(state.facing);
+        let center: Vector = std::array::from_fn(|k| center[k] + towards[k] - face.normal[k]);
+        let Some((&(face_row, face_column), next)) =
+            self.faces.iter().find(|(_, f)| f.normal == towards)
+        else {
+            return unsolvable!("unsupported cube layout");
+        };
+        let back = face.normal.map(|v| -v);
+        let Some(facing) = (0..4).find(|&f| next.towards(f) == back) else {
+            return invariant!("the faces of the cube are not square to each other");
+        };
+        Ok((
+            (
+                face_row * size + (dot(center, next.down) + size - 1) / 2,
+                face_column * size + (dot(center, next.right) + size - 1) / 2,
+            ),
+            facing,
+        ))
+    }
+
+    /// Draws the board, with the facing of the last visit on the tiles of `trail`.
+    fn draw(&self, trail: &HashMap<Coord, i32>) -> String {
+        let mut s = String::with_capacity((self.map.width() + 1) * self.map.height());
+        for ((x, y), t) in self.map.iter() {
+            s.push(match (trail.get(&(x as i32, y as i32)), t) {
+                (Some(0), _) => '>',
+                (Some(1), _) => 'v',
+                (Some(2), _) => '<',
+                (Some(3), _) => '^',
+                (_, Some(Tile::Open)) => '.',
+                (_, Some(Tile::Wall)) => '#',
+                (_, None) => ' ',
+            });
+            if y + 1 == self.map.width() {
+                s.push('\n');
+            }
+        }
+        s
+    }
+
+    fn top_left_open_tile(&self) -> Result<Coord> {
+        if let Some(y) = self
+            .map
+            .rows()
+            .next()
+            .and_then(|row| row.iter().position(|t| matches!(t, Some(Tile::Open))))
+        {
+            return Ok((0, y as i32));
+        }
+        unsolvable!("There is not open tile on the first row")
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Tile {
+    Open,
+    Wall,
+}
+
+#[derive(Debug)]
+pub enum Movement {
+    S(i32),
+    L,
+    R,
+}
+
+pub fn parse_input(input: &str) -> Result<(Map, Vec<Movement>)> {
+    if let Some((raw_map, raw_path)) = input.split_once("\n\n") {
+        let map = Grid::parse_padded(raw_map, None, |c| match c {
+            '.' => Ok(Some(Tile::Open)),
+            '#' => Ok(Some(Tile::Wall)),
+            ' ' => Ok(None),
+            _ => parse_err!("{c:?} is not a tile"),
+        })?;
+        let map = Map {
+            map,
+            cube_size: 0,
+            faces: HashMap::new(),
+        };
+
+        let mut path = vec![];
+        let mut stright = String::new();
+        let path_line = raw_map.lines().count() + 2;
+        for (i, c) in raw_path.trim_end().char_indices() {
+            if c.is_ascii_digit() {
+                stright.push(c);
+            } else if c == 'R' {
+                path.push(Movement::S(stright.parse().at(path_line, i + 1)?));
+                stright.clear();
+                path.push(Movement::R)
+            } else if c == 'L' {
+                path.push(Movement::S(stright.parse().at(path_line, i + 1)?));
+                stright.clear();
+                path.push(Movement::L)
+            } else {
+                return parse_err!("{c:?} is not a movement").at(path_line, i + 1);
+            }
+        }
+        if !stright.is_empty() {
+            path.push(Movement::S(stright.parse().at_line(path_line)?));
+        }
+        return Ok((map, path));
+    }
+    parse_err!("not a valid input for day 22")
+}
+
+/// The board is always folded like my input, only the walls and the path change.
+fn generate(rng: &mut Rng, turns: usize, _knobs: &Knobs) -> Result<String> {
+    const SIZE: usize = 50;
+    // the faces, by row and column of faces
+    const FACES: [&[usize]; 4] = [&[1, 2], &[1], &[0, 1], &[0]];
+    let mut lines = vec![];
+    for columns in FACES {
+        for _ in 0..SIZE {
+            let mut line = " ".repeat(columns[0] * SIZE);
+            for _ in columns[0] * SIZE..(columns[columns.len() - 1] + 1) * SIZE {
+                // the walk starts on the top left tile
+                let wall = !lines.is_empty() || line.len() > SIZE;
+                line.push(if wall && rng.chance(0.1) { '#' } else { '.' });
+            }
+            lines.push(line);
+        }
+    }
+    let mut path = rng.between(1, 50).to_string();
+    for _ in 0..turns {
+        path.push(*rng.choose(&['L', 'R']));
+        path.push_str(&rng.between(1, 50).to_string());
+    }
+    Ok(format!("{}\n\n{path}", lines.join("\n")))
+}
+
+#[test]
+fn example_input() {
+    let input = "        ...#
+        .#..
+        #...
+        ....
+...#.......#
+........#...
+..#....#....
+..........#.
+        ...#....
+        .....#..
+        .#......
+        ......#.
+
+10R5L5R10L4R5L5";
+    let (map, path) = parse_input(input).unwrap();
+    assert_eq!(part1(&map, &path).unwrap(), 6032);
+
+    let mut last = String::new();
+    animate(input, 1, &mut |frame: &str| {
+        last = frame.to_string();
+        true
+    })
+    .unwrap();
+    let rows: Vec<&str> = last.lines().collect();
+    assert_eq!(rows[0], "        >>v#    ");
+    assert_eq!(rows[5], ">>>v...>#.>>    ");
+}
+
+#[test]
+fn example_input_part2() {
+    let input = "        ...#
+        .#..
+        #...
+        ....
+...#.......#
+........#...
+..#....#....
+..........#.
+        ...#....
+        .....#..
+        .#......
+        ......#.
+
+10R5L5R10L4R5L5";
+    let (mut map, path) = parse_input(input).unwrap();
+    assert_eq!(part2(&mut map, &path).unwrap(), 5031);
+
+    // six faces that do not fold into a cube
+    let (mut map, path) = parse_input("...\n...\n\n1R1").unwrap();
+    assert!(part2(&mut map, &path).is_err());
+}
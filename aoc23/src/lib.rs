@@ -0,0 +1,272 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use aoc_common::{
+    parse_err, Animation, Answer, Error, Generator, Knob, Knobs, Result, Rng, Screen, Solution,
+};
+use aoc_grid::{Grid, Offset, Pos, DIRECTIONS8, DOWN, LEFT, RIGHT, UP};
+
+pub struct Day23;
+
+impl Solution for Day23 {
+    type Input = Ground;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        input.parse()
+    }
+
+    fn part1(ground: &Self::Input) -> Result<Answer> {
+        part1(&mut ground.clone()).map(Answer::from)
+    }
+
+    fn part2(ground: &Self::Input) -> Result<Answer> {
+        part2(&mut ground.clone()).map(Answer::from)
+    }
+
+    fn animations() -> Vec<Animation> {
+        vec![
+            Animation {
+                name: "elves spreading out",
+                part: 1,
+                animate: |input, screen| animate(input, Some(10), screen),
+            },
+            Animation {
+                name: "elves spreading out until they stop",
+                part: 2,
+                animate: |input, screen| animate(input, None, screen),
+            },
+        ]
+    }
+
+    fn generator() -> Option<Generator> {
+        Some(Generator {
+            size: "tiles on a side of the square",
+            default_size: 73,
+            knobs: &[Knob {
+                name: "density",
+                about: "chance of an elf on a tile",
+                default: "0.5",
+            }],
+            generate,
+        })
+    }
+}
+
+/// Shows the ground before every round, and after the last one.
+fn animate(input: &str, rounds: Option<usize>, screen: &mut dyn Screen) -> Result<()> {
+    let mut ground: Ground = input.parse()?;
+    let mut round = 0;
+    while screen.show(&ground.draw()) && rounds.is_none_or(|r| round < r) {
+        round += 1;
+        if ground.round() {
+            break;
+        }
+    }
+    Ok(())
+}
+
+pub type Integer = i32;
+
+pub fn part1(ground: &mut Ground) -> Result<Integer> {
+    for _ in 0..10 {
+        ground.round();
+    }
+    let result = ground.smallest_rectangle_tiles() - ground.elves.len() as Integer;
+
+    Ok(result)
+}
+
+pub fn part2(ground: &mut Ground) -> Result<Integer> {
+    let mut round = 0;
+    loop {
+        round += 1;
+        if ground.round() {
+            break;
+        }
+    }
+
+    Ok(round)
+}
+
+const NE: Offset = (-1, 1);
+const NW: Offset = (-1, -1);
+const SE: Offset = (1, 1);
+const SW: Offset = (1, -1);
+
+#[derive(Debug, Clone)]
+pub struct Ground {
+    /// Which tiles hold an elf, regrown before an elf can step off it.
+    grid: Grid<bool>,
+    elves: Vec<Pos>,
+    /// The three tiles to look at, then the step to propose.
+    dir: Vec<[Offset; 4]>,
+}
+
+impl Ground {
+    fn new(grid: Grid<bool>) -> Self {
+        let elves = grid.iter().filter(|(_, &e)| e).map(|(p, _)| p).collect();
+        Ground {
+            grid,
+            elves,
+            dir: vec![
+                [UP, NE, NW, UP],
+                [DOWN, SE, SW, DOWN],
+                [LEFT, NW, SW, LEFT],
+                [RIGHT, NE, SE, RIGHT],
+            ],
+        }
+    }
+
+    fn occupied(&self, pos: Pos, d: Offset) -> bool {
+        self.grid.offset(pos, d).is_some_and(|p| self.grid[p])
+    }
+
+    fn any_adjacent(&self, pos: Pos) -> bool {
+        DIRECTIONS8.iter().any(|&d| self.occupied(pos, d))
+    }
+
+    /// Makes sure every elf has a free tile around it on the grid.
+    fn grow(&mut self) {
+        let (height, width) = (self.grid.height(), self.grid.width());
+        if self
+            .elves
+            .iter()
+            .all(|&(r, c)| r > 0 && c > 0 && r + 1 < height && c + 1 < width)
+        {
+            return;
+        }
+        let margin = (height.max(width) / 4).max(2);
+        self.grid = self.grid.padded(margin, false);
+        for elf in &mut self.elves {
+            *elf = (elf.0 + margin, elf.1 + margin);
+        }
+    }
+
+    fn round(&mut self) -> bool {
+        self.grow();
+        // first half round
+        let mut possible_move: HashMap<Pos, Vec<usize>> = HashMap::new();
+        for (i, &pos) in self.elves.iter().enumerate() {
+            if !self.any_adjacent(pos) {
+                continue;
+            }
+            if let Some(dir) = self
+                .dir
+                .iter()
+                .find(|dir| dir[..3].iter().all(|&d| !self.occupied(pos, d)))
+            {
+                let next = self.grid.offset(pos, dir[3]).unwrap();
+                possible_move.entry(next).or_default().push(i);
+            }
+        }
+        // second half round, elves proposing the same tile do not move
+        let mut moved = false;
+        for (p, v) in possible_move {
+            if let [i] = v[..] {
+                self.grid[self.elves[i]] = false;
+                self.grid[p] = true;
+                self.elves[i] = p;
+                moved = true;
+            }
+        }
+        if !moved {
+            return true;
+        }
+        // rotate the direction
+        let first_dir = self.dir.remove(0);
+        self.dir.push(first_dir);
+        false
+    }
+
+    /// The top left and bottom right tiles of the smallest rectangle holding every elf.
+    fn bounds(&self) -> (Pos, Pos) {
+        let (mut min_x, mut min_y) = (usize::MAX, usize::MAX);
+        let (mut max_x, mut max_y) = (usize::MIN, usize::MIN);
+        for &(x, y) in &self.elves {
+            min_x = x.min(min_x);
+            min_y = y.min(min_y);
+            max_x = x.max(max_x);
+            max_y = y.max(max_y);
+        }
+        ((min_x, min_y), (max_x, max_y))
+    }
+
+    fn smallest_rectangle_tiles(&self) -> Integer {
+        let ((min_x, min_y), (max_x, max_y)) = self.bounds();
+        ((max_x - min_x + 1) * (max_y - min_y + 1)) as Integer
+    }
+
+    fn draw(&self) -> String {
+        let ((min_x, min_y), (max_x, max_y)) = self.bounds();
+        let mut s = String::new();
+        for row in self.grid.rows().skip(min_x).take(max_x - min_x + 1) {
+            s.extend(
+                row[min_y..=max_y]
+                    .iter()
+                    .map(|&e| if e { '#' } else { '.' }),
+            );
+            s.push('\n');
+        }
+        s
+    }
+}
+
+impl FromStr for Ground {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let grid = Grid::parse(s, |c| match c {
+            '#' => Ok(true),
+            '.' => Ok(false),
+            _ => parse_err!("{c:?} is not a tile"),
+        })?;
+        if !grid.iter().any(|(_, &e)| e) {
+            return parse_err!("there are no elves on the ground");
+        }
+        Ok(Ground::new(grid))
+    }
+}
+
+fn generate(rng: &mut Rng, side: usize, knobs: &Knobs) -> Result<String> {
+    let side = side.max(1);
+    let density: f64 = knobs.get("density")?;
+    let mut tiles: Vec<Vec<u8>> = (0..side)
+        .map(|_| {
+            (0..side)
+                .map(|_| if rng.chance(density) { b'#' } else { b'.' })
+                .collect()
+        })
+        .collect();
+    // there is always an elf
+    if tiles.iter().flatten().all(|&t| t == b'.') {
+        tiles[rng.index(side)][rng.index(side)] = b'#';
+    }
+    let lines: Vec<String> = tiles
+        .into_iter()
+        .map(|row| String::from_utf8(row).unwrap())
+        .collect();
+    Ok(lines.join("\n"))
+}
+
+#[test]
+fn example_input() {
+    let input = "....#..
+..###.#
+#...#.#
+.#...##
+#.###..
+##.#.##
+.#..#..";
+    let ground: Ground = input.parse().unwrap();
+    assert_eq!(part1(&mut ground.clone()).unwrap(), 110);
+    assert_eq!(part2(&mut ground.clone()).unwrap(), 20);
+
+    let mut frames = vec![];
+    animate(input, Some(10), &mut |frame: &str| {
+        frames.push(frame.to_string());
+        true
+    })
+    .unwrap();
+    assert_eq!(frames.len(), 11);
+    assert_eq!(frames[0], format!("{input}\n"));
+}
@@ -0,0 +1,368 @@
+use std::str::FromStr;
+
+use aoc_common::{
+    parse_err, unsolvable, Animation, Answer, Error, Generator, Knobs, Position, Result, Rng,
+    Screen, Solution,
+};
+use aoc_grid::Grid;
+use aoc_search::{bfs, Path};
+use tracing::{debug, debug_span};
+
+pub struct Day24;
+
+impl Solution for Day24 {
+    type Input = (Vec<Map>, (usize, usize), (usize, usize));
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        let mut map: Map = input.parse()?;
+        let (start_pos, end_pos) = map.start_and_end();
+        Ok((build_map_cache(&mut map), start_pos, end_pos))
+    }
+
+    fn part1((map_cache, start_pos, end_pos): &Self::Input) -> Result<Answer> {
+        part1(map_cache, *start_pos, *end_pos).map(Answer::from)
+    }
+
+    fn part2((map_cache, start_pos, end_pos): &Self::Input) -> Result<Answer> {
+        part2(map_cache, *start_pos, *end_pos).map(Answer::from)
+    }
+
+    fn animations() -> Vec<Animation> {
+        vec![
+            Animation {
+                name: "blizzard crossing",
+                part: 1,
+                animate: |input, screen| animate(input, 1, screen),
+            },
+            Animation {
+                name: "blizzard crossing for the snacks",
+                part: 2,
+                animate: |input, screen| animate(input, 3, screen),
+            },
+        ]
+    }
+
+    fn generator() -> Option<Generator> {
+        Some(Generator {
+            size: "columns of the valley, at most 126",
+            default_size: 100,
+            knobs: &[],
+            generate,
+        })
+    }
+}
+
+/// Shows the valley minute by minute while the expedition crosses it `trips` times.
+fn animate(input: &str, trips: usize, screen: &mut dyn Screen) -> Result<()> {
+    let mut map: Map = input.parse()?;
+    let (mut from, mut to) = map.start_and_end();
+    let map_cache = build_map_cache(&mut map);
+    let mut time = 0;
+    for trip in 0..trips {
+        let Some(path) = expedition_path(&map_cache, from, to, time) else {
+            return unsolvable!("the blizzards never let the expedition through");
+        };
+        // a trip starts where the last one ended
+        for &((x, y), cycle_time) in path.nodes.iter().skip(usize::from(trip > 0)) {
+            if !screen.show(&map_cache[cycle_time].draw(x, y)) {
+                return Ok(());
+            }
+        }
+        time += path.cost;
+        (from, to) = (to, from);
+    }
+    Ok(())
+}
+
+pub fn part1(
+    map_cache: &[Map],
+    start_pos: (usize, usize),
+    end_pos: (usize, usize),
+) -> Result<usize> {
+    avoid_blizzards(map_cache, start_pos, end_pos, 0)
+}
+
+pub fn part2(
+    map_cache: &[Map],
+    start_pos: (usize, usize),
+    end_pos: (usize, usize),
+) -> Result<usize> {
+    let find_time = avoid_blizzards(map_cache, start_pos, end_pos, 0)?;
+    let find_time = avoid_blizzards(map_cache, end_pos, start_pos, find_time)?;
+    avoid_blizzards(map_cache, start_pos, end_pos, find_time)
+}
+
+pub fn build_map_cache(map: &mut Map) -> Vec<Map> {
+    let mut cache = vec![];
+    let cycle_count = lcm(map.blizzards.height() - 2, map.blizzards.width() - 2);
+    debug!(cycle = cycle_count, "blizzards repeat");
+    for _ in 0..cycle_count {
+        cache.push(map.clone());
+        map.next();
+    }
+    cache
+}
+
+fn lcm(small: usize, big: usize) -> usize {
+    let mut m = 1;
+    while m < small {
+        if (m * big).is_multiple_of(small) {
+            return m * big;
+        }
+        m += 1;
+    }
+    small * big
+}
+
+/// The minute the expedition reaches `end` when it leaves `start` at `start_time`.
+fn avoid_blizzards(
+    map_cache: &[Map],
+    start: (usize, usize),
+    end: (usize, usize),
+    start_time: usize,
+) -> Result<usize> {
+    let _span = debug_span!("trip", ?start, ?end, start_time).entered();
+    match expedition_path(map_cache, start, end, start_time) {
+        Some(path) => {
+            debug!(minutes = path.cost, "trip done");
+            Ok(start_time + path.cost)
+        }
+        None => unsolvable!("the blizzards never let the expedition through"),
+    }
+}
+
+/// The positions of the expedition minute by minute, with the minute in the blizzard cycle.
+pub fn expedition_path(
+    map_cache: &[Map],
+    start: (usize, usize),
+    end: (usize, usize),
+    start_time: usize,
+) -> Option<Path<((usize, usize), usize)>> {
+    let cycle = map_cache.len();
+    bfs(
+        [(start, start_time % cycle)],
+        |&(cur, cycle_time)| {
+            let cycle_time = (cycle_time + 1) % cycle;
+            let map = &map_cache[cycle_time];
+            let (x, y) = cur;
+            [
+                (x.saturating_sub(1), y),
+                (x + 1, y),
+                (x, y + 1),
+                (x, y.saturating_sub(1)),
+                cur,
+            ]
+            .into_iter()
+            .filter(move |&next| next == end || map.moveable(next.0, next.1) || next == start)
+            .map(move |next| (next, cycle_time))
+        },
+        |&(cur, _)| cur == end,
+    )
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+pub struct Map {
+    blizzards: Grid<u8>, // bit 0 up, 1 down, 2 right, 3 left
+    start: (usize, usize),
+    end: (usize, usize),
+}
+
+impl Map {
+    fn next(&mut self) {
+        let mut next_blizzards = self.blizzards.map(|_| 0);
+        // start and end never has blizzard
+        for ((x, y), &v) in self.blizzards.iter() {
+            for d in 0..4 {
+                if v & (1 << d) != 0 {
+                    next_blizzards[self.next_pos(x, y, d)] |= 1 << d;
+                }
+            }
+        }
+        self.blizzards = next_blizzards;
+    }
+
+    fn wrap(&self, x: usize, y: usize) -> (usize, usize) {
+        let (height, width) = (self.blizzards.height(), self.blizzards.width());
+        let mut nx = x;
+        let mut ny = y;
+        if x == 0 {
+            nx = height - 2;
+        }
+        if y == 0 {
+            ny = width - 2;
+        }
+        if x == height - 1 {
+            nx = 1;
+        }
+        if y == width - 1 {
+            ny = 1;
+        }
+        (nx, ny)
+    }
+
+    fn next_pos(&self, x: usize, y: usize, c: usize) -> (usize, usize) {
+        match c {
+            0 => self.wrap(x - 1, y),
+            1 => self.wrap(x + 1, y),
+            2 => self.wrap(x, y + 1),
+            3 => self.wrap(x, y - 1),
+            _ => unreachable!("{}", c),
+        }
+    }
+
+    pub fn start_and_end(&self) -> ((usize, usize), (usize, usize)) {
+        (self.start, self.end)
+    }
+
+    fn moveable(&self, x: usize, y: usize) -> bool {
+        x > 0
+            && y > 0
+            && x < self.blizzards.height() - 1
+            && y < self.blizzards.width() - 1
+            && self.blizzards[(x, y)] == 0
+    }
+
+    fn draw(&self, e_x: usize, e_y: usize) -> String {
+        let (height, width) = (self.blizzards.height(), self.blizzards.width());
+        let mut s = String::new();
+        for ((x, y), &b) in self.blizzards.iter() {
+            if x == e_x && y == e_y {
+                s.push('E')
+            } else if (x == 0 && y == self.start.1) || (x == height - 1 && y == self.end.1) {
+                s.push('.')
+            } else if x == 0 || y == 0 || x == height - 1 || y == width - 1 {
+                s.push('#');
+            } else {
+                s.push(match b {
+                    0 => '.',
+                    0b0001 => '^',
+                    0b0010 => 'v',
+                    0b0100 => '>',
+                    0b1000 => '<',
+                    _ => char::from_digit(b.count_ones(), 10).unwrap(),
+                })
+            }
+            if y == width - 1 {
+                s.push('\n');
+            }
+        }
+        s
+    }
+}
+
+/// Every minute of a cycle is cached, and a cycle is as long as the lcm of the inner sizes.
+const MAX_SIZE: usize = 128;
+
+impl FromStr for Map {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let lines: Vec<&str> = s.lines().map(|l| l.trim()).collect();
+        let height = lines.len();
+        let width = lines.first().map_or(0, |l| l.len());
+        if !(3..=MAX_SIZE).contains(&height) || !(3..=MAX_SIZE).contains(&width) {
+            return parse_err!("the valley should be from 3x3 to {MAX_SIZE}x{MAX_SIZE}");
+        }
+        let gap = |x: usize| {
+            let line = lines[x];
+            match line.chars().position(|v| v == '.') {
+                Some(y)
+                    if line.chars().filter(|&v| v == '.').count() == 1
+                        && y > 0
+                        && y < width - 1 =>
+                {
+                    Ok(y)
+                }
+                _ => parse_err!("there should be one gap in the wall").at_line(x + 1),
+            }
+        };
+        let start = gap(0)?;
+        let end = gap(height - 1)?;
+        let mut blizzards = Grid::new(height, width, 0);
+        for (x, line) in lines.iter().enumerate() {
+            if line.len() != width {
+                return parse_err!("the valley is not a rectangle").at_line(x + 1);
+            }
+            for (y, c) in line.char_indices() {
+                let cell = &mut blizzards[(x, y)];
+                let wall = x == 0 || y == 0 || x == height - 1 || y == width - 1;
+                match c {
+                    '#' if wall => (),
+                    '.' if !wall || (x == 0 && y == start) || (x == height - 1 && y == end) => (),
+                    '^' if !wall => *cell = 1 << 0,
+                    'v' if !wall => *cell = 1 << 1,
+                    '>' if !wall => *cell = 1 << 2,
+                    '<' if !wall => *cell = 1 << 3,
+                    _ => return parse_err!("{c:?} is out of place").at(x + 1, y + 1),
+                }
+            }
+        }
+        Ok(Map {
+            start: (0, start),
+            end: (height - 1, end),
+            blizzards,
+        })
+    }
+}
+
+/// A valley shaped like the real one, where no blizzard blows up or down the columns of the
+/// gaps, so none leaves the valley.
+fn generate(rng: &mut Rng, columns: usize, _knobs: &Knobs) -> Result<String> {
+    let width = columns.clamp(1, MAX_SIZE - 2);
+    let height = (width * 35 / 100).max(1);
+    for _ in 0..1000 {
+        let mut lines = vec![format!("#.{}", "#".repeat(width))];
+        for _ in 0..height {
+            let mut line = "#".to_string();
+            for y in 0..width {
+                let blizzards: &[char] = if y == 0 || y == width - 1 {
+                    &['<', '>']
+                } else {
+                    &['<', '>', '^', 'v']
+                };
+                line.push(if rng.chance(0.7) {
+                    *rng.choose(blizzards)
+                } else {
+                    '.'
+                });
+            }
+            line.push('#');
+            lines.push(line);
+        }
+        lines.push(format!("{}.#", "#".repeat(width)));
+        let valley = lines.join("\n");
+
+        let mut map: Map = valley.parse()?;
+        let (start, end) = map.start_and_end();
+        if part2(&build_map_cache(&mut map), start, end).is_ok() {
+            return Ok(valley);
+        }
+    }
+    unsolvable!("could not make a valley the expedition can cross")
+}
+
+#[test]
+fn example_input() {
+    let input = "#.######
+    #>>.<^<#
+    #.<..<<#
+    #>v.><>#
+    #<^v^^>#
+    ######.#";
+    let mut map: Map = input.parse().unwrap();
+    let (start, end) = map.start_and_end();
+    println!("{}", map.draw(start.0, start.1));
+    let map_cache = build_map_cache(&mut map);
+    assert_eq!(18, part1(&map_cache, start, end).unwrap());
+    let path = expedition_path(&map_cache, start, end, 0).unwrap();
+    assert_eq!(path.nodes.len(), 19);
+    assert_eq!(54, part2(&map_cache, start, end).unwrap());
+
+    let mut frames = 0;
+    animate(input, 3, &mut |_: &str| {
+        frames += 1;
+        true
+    })
+    .unwrap();
+    assert_eq!(frames, 55);
+}
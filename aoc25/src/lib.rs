@@ -0,0 +1,145 @@
+use aoc_common::{parse_err, Answer, Generator, Knobs, Position, Result, Rng, Solution};
+
+pub struct Day25;
+
+impl Solution for Day25 {
+    type Input = Vec<String>;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(parse_input(input)?.into_iter().map(String::from).collect())
+    }
+
+    fn part1(snafu_numbers: &Self::Input) -> Result<Answer> {
+        part1(snafu_numbers).map(Answer::from)
+    }
+
+    fn part2(_: &Self::Input) -> Result<Answer> {
+        Ok(Answer::None)
+    }
+
+    fn generator() -> Option<Generator> {
+        Some(Generator {
+            size: "numbers",
+            default_size: 120,
+            knobs: &[],
+            generate,
+        })
+    }
+}
+
+type Decimal = i64;
+
+/// Longer numbers could overflow a `Decimal` once summed.
+const MAX_DIGITS: usize = 20;
+
+pub fn parse_input(input: &str) -> Result<Vec<&str>> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, l)| {
+            let l = l.trim();
+            if l.len() > MAX_DIGITS {
+                return parse_err!("{} has more than {MAX_DIGITS} digits", l).at_line(i + 1);
+            }
+            snafu_to_decimal(l).at_line(i + 1)?;
+            Ok(l)
+        })
+        .collect()
+}
+
+pub fn part1<S: AsRef<str>>(snafu_numbers: &[S]) -> Result<String> {
+    let result: Decimal = snafu_numbers
+        .iter()
+        .map(|n| snafu_to_decimal(n.as_ref()))
+        .sum::<Result<Decimal>>()?;
+    let result = decimal_to_snafu(result);
+    Ok(result)
+}
+
+fn snafu_to_decimal(s: &str) -> Result<Decimal> {
+    // 2 1 0 - =
+    let snafu = s.trim().chars().rev();
+    let mut num = 0;
+    let mut base = 1;
+    for c in snafu {
+        num += base
+            * match c {
+                '2' => 2,
+                '1' => 1,
+                '0' => 0,
+                '-' => -1,
+                '=' => -2,
+                _ => return parse_err!("not a valid SNAFU number {}", s),
+            };
+        base *= 5;
+    }
+
+    Ok(num)
+}
+
+fn decimal_to_snafu(num: Decimal) -> String {
+    let mut snafu = String::new();
+    // 4890 => 2=-1=0
+    let mut num = num;
+    while num > 0 {
+        let remain = num % 5;
+        num /= 5;
+        match remain {
+            0 => snafu.push('0'),
+            1 => snafu.push('1'),
+            2 => snafu.push('2'),
+            3 => {
+                snafu.push('=');
+                num += 1
+            }
+            4 => {
+                snafu.push('-');
+                num += 1
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    snafu.chars().rev().collect()
+}
+
+/// Positive numbers, as short as it takes for their sum to fit in a `Decimal`.
+fn generate(rng: &mut Rng, numbers: usize, _knobs: &Knobs) -> Result<String> {
+    let count = numbers.max(1);
+    // the largest number of `digits` digits is all 2s, (5^digits - 1) / 2
+    let digits = (1..=MAX_DIGITS as u32)
+        .take_while(|&d| (5 as Decimal).pow(d) / 2 <= Decimal::MAX / count as Decimal)
+        .last()
+        .unwrap_or(1) as i64;
+    let lines: Vec<String> = (0..count)
+        .map(|_| {
+            let len = rng.between(1, digits);
+            let mut snafu = rng.choose(&['1', '2']).to_string();
+            for _ in 1..len {
+                snafu.push(*rng.choose(&['2', '1', '0', '-', '=']));
+            }
+            snafu
+        })
+        .collect();
+    Ok(lines.join("\n"))
+}
+
+#[test]
+fn example_input() {
+    let input = "1=-0-2
+    12111
+    2=0=
+    21
+    2=01
+    111
+    20012
+    112
+    1=-1=
+    1-12
+    12
+    1=
+    122";
+    assert_eq!(snafu_to_decimal("1=-0-2").unwrap(), 1747);
+    let snafu_numbers: Vec<&str> = input.lines().map(|l| l.trim()).collect();
+    assert_eq!(&part1(&snafu_numbers).unwrap(), "2=-1=0");
+}